@@ -0,0 +1,177 @@
+use super::config::find_project_by_path;
+use super::git::{
+    classify_failure, git_command, parse_commit_date, validate_authors, validate_date,
+};
+use super::repo::{resolve_repo_path, revision_args};
+use chrono::{Datelike, Timelike};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ActivityHeatmap {
+    pub total_commits: usize,
+    pub calendar: Vec<DayActivity>,
+    pub hour_weekday: Vec<Vec<usize>>, // [weekday][hour]，周一为 0
+    pub months: Vec<MonthActivity>,
+    pub projects: Vec<ProjectActivity>,
+    pub most_active_month: Option<String>,
+    pub after_hours_commits: usize,
+    pub weekend_commits: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DayActivity {
+    pub date: String,
+    pub commit_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MonthActivity {
+    pub month: String,
+    pub commit_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectActivity {
+    pub name: String,
+    pub commit_count: usize,
+}
+
+// 工作时间按提交者本地时间计算，[9:00, 18:00) 之外的工作日提交算作非工作时间
+const WORK_START_HOUR: u32 = 9;
+const WORK_END_HOUR: u32 = 18;
+
+#[tauri::command]
+pub fn get_activity_heatmap(
+    paths: Vec<String>,
+    since: String,
+    until: String,
+    authors: Vec<String>,
+) -> Result<ActivityHeatmap, String> {
+    validate_date(&since)?;
+    validate_date(&until)?;
+    validate_authors(&authors)?;
+
+    let mut calendar: BTreeMap<String, usize> = BTreeMap::new();
+    let mut months: BTreeMap<String, usize> = BTreeMap::new();
+    let mut projects: BTreeMap<String, usize> = BTreeMap::new();
+    let mut hour_weekday = vec![vec![0usize; 24]; 7];
+    let mut total_commits = 0;
+    let mut after_hours_commits = 0;
+    let mut weekend_commits = 0;
+
+    for path in &paths {
+        let repo_path = resolve_repo_path(path)?;
+        let dates = match fetch_commit_dates(&repo_path, &since, &until, &authors) {
+            Ok(dates) => dates,
+            Err(e) => {
+                eprintln!("获取提交时间失败 ({}): {}", path, e);
+                continue;
+            }
+        };
+
+        let project_name = find_project_by_path(path)
+            .map(|p| p.name)
+            .unwrap_or_else(|| folder_name(path));
+
+        for date in &dates {
+            let dt = match parse_commit_date(date) {
+                Some(dt) => dt,
+                None => continue,
+            };
+            total_commits += 1;
+            *calendar
+                .entry(dt.format("%Y-%m-%d").to_string())
+                .or_insert(0) += 1;
+            *months.entry(dt.format("%Y-%m").to_string()).or_insert(0) += 1;
+            *projects.entry(project_name.clone()).or_insert(0) += 1;
+
+            let weekday = dt.weekday().num_days_from_monday() as usize;
+            let hour = dt.hour();
+            hour_weekday[weekday][hour as usize] += 1;
+
+            if weekday >= 5 {
+                weekend_commits += 1;
+            } else if !(WORK_START_HOUR..WORK_END_HOUR).contains(&hour) {
+                after_hours_commits += 1;
+            }
+        }
+    }
+
+    let most_active_month = months
+        .iter()
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(a.0)))
+        .map(|(month, _)| month.clone());
+
+    let mut projects: Vec<ProjectActivity> = projects
+        .into_iter()
+        .map(|(name, commit_count)| ProjectActivity { name, commit_count })
+        .collect();
+    projects.sort_by_key(|p| Reverse(p.commit_count));
+
+    Ok(ActivityHeatmap {
+        total_commits,
+        calendar: calendar
+            .into_iter()
+            .map(|(date, commit_count)| DayActivity { date, commit_count })
+            .collect(),
+        hour_weekday,
+        months: months
+            .into_iter()
+            .map(|(month, commit_count)| MonthActivity {
+                month,
+                commit_count,
+            })
+            .collect(),
+        projects,
+        most_active_month,
+        after_hours_commits,
+        weekend_commits,
+    })
+}
+
+fn folder_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+// 只取提交时间，不受 get_git_log 的 1000 条上限限制，年度热力图也能完整统计
+fn fetch_commit_dates(
+    path: &str,
+    since: &str,
+    until: &str,
+    authors: &[String],
+) -> Result<Vec<String>, String> {
+    let mut args = vec![
+        "log".to_string(),
+        format!("--since={}", since),
+        format!("--until={}", until),
+        "--format=%ai".to_string(),
+        "--no-merges".to_string(),
+    ];
+    for author in authors {
+        args.push(format!("--author={}", author));
+    }
+    args.extend(revision_args(path));
+
+    let output = git_command()
+        .args(&args)
+        .current_dir(path)
+        .output()
+        .map_err(|e| format!("获取 Git 日志失败: {}", e))?;
+
+    if !output.status.success() {
+        return Err(classify_failure("Git log 执行失败", path, &output.stderr).into());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect())
+}
@@ -0,0 +1,293 @@
+use super::dedup::pipe_through;
+use super::git::{parse_numstat_z, GitCommit, FIELD_SEPARATOR};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const LFS_POINTER_PREFIX: &[u8] = b"version https://git-lfs.github.com/spec/v1";
+// LFS 规范限定指针文件不超过 1024 字节
+const LFS_POINTER_MAX_SIZE: u64 = 1024;
+// 指针文件只有 version、oid、size 三行，改动行数更多的不可能是指针
+const LFS_POINTER_MAX_LINES: usize = 3;
+
+/// 不作为代码改动统计的资源文件
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssetKind {
+    Lfs,    // Git LFS 指针，大小取指针中记录的实际文件大小
+    Binary, // 直接提交到仓库的二进制文件
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct AssetSummary {
+    pub file_count: usize, // 抽样中更新过的资源文件数
+    pub total_size: u64,   // 这些文件最新版本的大小之和（字节）
+    pub updates: Vec<AssetUpdate>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssetUpdate {
+    pub path: String,
+    pub kind: AssetKind,
+    pub size: Option<u64>, // 最新版本的大小，已删除时为空
+    pub change_count: usize,
+}
+
+// 待确认的变更文件，spec 为 cat-file 可读取的对象名
+struct Candidate {
+    commit: usize,
+    file: usize,
+    spec: String,
+    binary: bool,
+    deleted: bool,
+}
+
+/// 标注提交中的 LFS 指针和二进制文件及其大小，读取失败时保持原样
+pub(crate) fn flag_assets(repo_path: &str, commits: &mut [GitCommit]) {
+    if commits.is_empty() {
+        return;
+    }
+    let candidates = match find_candidates(repo_path, commits) {
+        Ok(candidates) if !candidates.is_empty() => candidates,
+        Ok(_) => return,
+        Err(e) => {
+            eprintln!("识别资源文件失败 ({}): {}", repo_path, e);
+            return;
+        }
+    };
+    let sizes = match object_sizes(repo_path, &candidates) {
+        Ok(sizes) => sizes,
+        Err(e) => {
+            eprintln!("读取资源文件大小失败 ({}): {}", repo_path, e);
+            return;
+        }
+    };
+
+    // 只有足够小的文本文件才需要读取内容确认是否为 LFS 指针
+    let pointer_specs: Vec<&str> = candidates
+        .iter()
+        .zip(&sizes)
+        .filter(|(c, size)| !c.binary && size.is_some_and(|s| s <= LFS_POINTER_MAX_SIZE))
+        .map(|(c, _)| c.spec.as_str())
+        .collect();
+    let pointers = lfs_pointer_sizes(repo_path, &pointer_specs).unwrap_or_else(|e| {
+        eprintln!("读取 LFS 指针失败 ({}): {}", repo_path, e);
+        HashMap::new()
+    });
+
+    for (candidate, size) in candidates.iter().zip(sizes) {
+        let (kind, size) = match pointers.get(&candidate.spec) {
+            Some(real_size) => (AssetKind::Lfs, Some(*real_size)),
+            None if candidate.binary => (AssetKind::Binary, size),
+            None => continue,
+        };
+        let file = &mut commits[candidate.commit].files[candidate.file];
+        file.asset = Some(kind);
+        file.size = if candidate.deleted { None } else { size };
+    }
+}
+
+/// 按 numstat 找出二进制文件（行数为 "-"）和改动行数不超过指针行数的文件。
+/// 使用 -z 读取原始路径，否则非 ASCII 路径会被转义，拼出的对象名无法读取
+fn find_candidates(repo_path: &str, commits: &[GitCommit]) -> Result<Vec<Candidate>, String> {
+    let format = format!("--pretty=format:{}%H", FIELD_SEPARATOR);
+    let args = [
+        "log",
+        "--no-walk=unsorted",
+        "--stdin",
+        "--numstat",
+        "-z",
+        "--no-renames",
+        format.as_str(),
+    ];
+    let input: String = commits.iter().map(|c| format!("{}\n", c.hash)).collect();
+    let output = pipe_through(repo_path, &args, input.into_bytes())?;
+
+    // 提交哈希 -> (路径 -> 是否二进制)
+    let mut numstat: HashMap<String, HashMap<String, bool>> = HashMap::new();
+    for (hash, entries) in parse_numstat_z(&output) {
+        let files = numstat.entry(hash).or_default();
+        for entry in entries {
+            let binary = entry.added.is_none() && entry.deleted.is_none();
+            let small = matches!(
+                (entry.added, entry.deleted),
+                (Some(a), Some(d)) if a <= LFS_POINTER_MAX_LINES && d <= LFS_POINTER_MAX_LINES
+            );
+            if binary || small {
+                files.insert(entry.path, binary);
+            }
+        }
+    }
+
+    let mut candidates = Vec::new();
+    for (commit_index, commit) in commits.iter().enumerate() {
+        let Some(files) = numstat.get(&commit.hash) else {
+            continue;
+        };
+        for (file_index, file) in commit.files.iter().enumerate() {
+            // 重命名的 name-status 条目为 "旧路径\t新路径"
+            let path = file.path.rsplit('\t').next().unwrap_or(&file.path);
+            let Some(&binary) = files.get(path) else {
+                continue;
+            };
+            let deleted = file.status == "D";
+            // 已删除的文件从父提交中读取
+            let spec = if deleted {
+                format!("{}^:{}", commit.hash, path)
+            } else {
+                format!("{}:{}", commit.hash, path)
+            };
+            candidates.push(Candidate {
+                commit: commit_index,
+                file: file_index,
+                spec,
+                binary,
+                deleted,
+            });
+        }
+    }
+    Ok(candidates)
+}
+
+/// 各候选对象的字节数，顺序与输入一致，对象不存在时为空
+fn object_sizes(repo_path: &str, candidates: &[Candidate]) -> Result<Vec<Option<u64>>, String> {
+    let input: String = candidates.iter().map(|c| format!("{}\n", c.spec)).collect();
+    let output = pipe_through(
+        repo_path,
+        &["cat-file", "--batch-check=%(objecttype) %(objectsize)"],
+        input.into_bytes(),
+    )?;
+    Ok(String::from_utf8_lossy(&output)
+        .lines()
+        .map(|line| match line.split_once(' ') {
+            Some(("blob", size)) => size.trim().parse().ok(),
+            _ => None,
+        })
+        .collect())
+}
+
+/// 读取候选对象的内容，返回其中 LFS 指针记录的实际文件大小
+fn lfs_pointer_sizes(repo_path: &str, specs: &[&str]) -> Result<HashMap<String, u64>, String> {
+    let mut pointers = HashMap::new();
+    if specs.is_empty() {
+        return Ok(pointers);
+    }
+    let input: String = specs.iter().map(|s| format!("{}\n", s)).collect();
+    let output = pipe_through(repo_path, &["cat-file", "--batch"], input.into_bytes())?;
+
+    // 每个对象输出为 "<oid> <type> <size>\n<内容>\n"，不存在时为 "<name> missing\n"
+    let mut rest = output.as_slice();
+    for spec in specs {
+        let Some(header_end) = rest.iter().position(|&b| b == b'\n') else {
+            break;
+        };
+        let header = String::from_utf8_lossy(&rest[..header_end]).to_string();
+        rest = &rest[header_end + 1..];
+        let Some(size) = header
+            .rsplit(' ')
+            .next()
+            .and_then(|s| s.parse::<usize>().ok())
+        else {
+            continue;
+        };
+        let Some(content) = rest.get(..size) else {
+            break;
+        };
+        if let Some(real_size) = parse_lfs_pointer(content) {
+            pointers.insert(spec.to_string(), real_size);
+        }
+        rest = rest.get(size + 1..).unwrap_or_default();
+    }
+    Ok(pointers)
+}
+
+fn parse_lfs_pointer(content: &[u8]) -> Option<u64> {
+    if !content.starts_with(LFS_POINTER_PREFIX) {
+        return None;
+    }
+    String::from_utf8_lossy(content)
+        .lines()
+        .find_map(|line| line.strip_prefix("size ")?.trim().parse().ok())
+}
+
+/// 汇总抽样提交中的资源更新，按改动次数、大小降序排列。
+/// 提交按时间倒序，每个文件首次出现时记录的就是最新版本的大小
+pub(crate) fn summarize_assets(commits: &[GitCommit]) -> AssetSummary {
+    let mut updates: Vec<AssetUpdate> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for file in commits.iter().flat_map(|c| &c.files) {
+        let Some(kind) = file.asset else {
+            continue;
+        };
+        match index.get(&file.path) {
+            Some(&i) => updates[i].change_count += 1,
+            None => {
+                index.insert(file.path.clone(), updates.len());
+                updates.push(AssetUpdate {
+                    path: file.path.clone(),
+                    kind,
+                    size: file.size,
+                    change_count: 1,
+                });
+            }
+        }
+    }
+
+    let file_count = updates.len();
+    let total_size = updates.iter().filter_map(|u| u.size).sum();
+    updates.sort_by(|a, b| {
+        b.change_count
+            .cmp(&a.change_count)
+            .then(b.size.cmp(&a.size))
+    });
+    updates.truncate(20);
+    AssetSummary {
+        file_count,
+        total_size,
+        updates,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::git::fetch_git_log_limited;
+    use crate::commands::test_util::TempRepo;
+
+    const POINTER: &str = "version https://git-lfs.github.com/spec/v1\n\
+        oid sha256:4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393\n\
+        size 12345\n";
+
+    #[test]
+    fn flags_assets_with_non_ascii_paths() {
+        let repo = TempRepo::new("asset-non-ascii");
+        repo.write("说明.md", "# 说明\n");
+        repo.write("资源/图标.png", [0x89u8, b'P', b'N', b'G', 0, 1, 2, 3]);
+        repo.write("模型/场景.bin", POINTER);
+        repo.commit("add assets");
+        repo.write("资源/图标.png", [0x89u8, b'P', 0, 9]);
+        repo.commit("update icon");
+
+        let commits =
+            fetch_git_log_limited(&repo.path(), "2000-01-01", "2099-12-31", &[], 10).unwrap();
+        let file = |path: &str| {
+            commits[1]
+                .files
+                .iter()
+                .find(|f| f.path == path)
+                .unwrap_or_else(|| panic!("{} not found", path))
+        };
+
+        assert_eq!(file("说明.md").asset, None);
+        assert_eq!(file("资源/图标.png").asset, Some(AssetKind::Binary));
+        assert_eq!(file("资源/图标.png").size, Some(8));
+        assert_eq!(file("模型/场景.bin").asset, Some(AssetKind::Lfs));
+        assert_eq!(file("模型/场景.bin").size, Some(12345));
+
+        // 抽样按时间倒序，汇总取最新版本的大小
+        let summary = summarize_assets(&commits);
+        assert_eq!(summary.file_count, 2);
+        assert_eq!(summary.total_size, 12345 + 4);
+        assert_eq!(summary.updates[0].path, "资源/图标.png");
+        assert_eq!(summary.updates[0].change_count, 2);
+    }
+}
@@ -0,0 +1,113 @@
+use super::config::find_project_by_path;
+use super::git::GitCommit;
+use serde::{Deserialize, Serialize};
+
+/// 自动化提交的类别，报告中默认不计入工作量
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AutomationKind {
+    Bot,        // 机器人账号的其他提交
+    Dependency, // 依赖升级，如 dependabot、renovate
+    Release,    // 版本发布、changelog 生成
+    Formatting, // 格式化工具的批量改动
+    MassRename, // 大规模移动或重命名文件
+}
+
+// 作者名或邮箱中出现即视为机器人账号
+const BOT_AUTHOR_MARKERS: [&str; 10] = [
+    "[bot]",
+    "dependabot",
+    "renovate",
+    "github-actions",
+    "gitlab-ci",
+    "semantic-release",
+    "release-please",
+    "greenkeeper",
+    "snyk-bot",
+    "pre-commit-ci",
+];
+
+const DEPENDENCY_PREFIXES: [&str; 5] = [
+    "chore(deps",
+    "build(deps",
+    "fix(deps",
+    "chore(deps-dev",
+    "update dependency ",
+];
+
+const RELEASE_PREFIXES: [&str; 4] = [
+    "chore(release",
+    "chore: release",
+    "release: ",
+    "[maven-release-plugin]",
+];
+
+const FORMATTING_PREFIXES: [&str; 10] = [
+    "cargo fmt",
+    "run cargo fmt",
+    "apply prettier",
+    "run prettier",
+    "prettier",
+    "run black",
+    "gofmt",
+    "clang-format",
+    "style: format",
+    "format code",
+];
+
+// 至少这么多文件且全部为重命名时视为批量移动
+const MASS_RENAME_MIN_FILES: usize = 10;
+
+/// 按作者和提交说明识别自动化提交，不需要文件列表，统计提交数时也可使用
+pub(crate) fn detect_automation(
+    author: &str,
+    email: &str,
+    message: &str,
+) -> Option<AutomationKind> {
+    let subject = message.trim().to_lowercase();
+    let is_bot = {
+        let author = author.to_lowercase();
+        let email = email.to_lowercase();
+        BOT_AUTHOR_MARKERS
+            .iter()
+            .any(|m| author.contains(m) || email.contains(m))
+            || author.ends_with(" bot")
+            || author.ends_with("-bot")
+    };
+
+    // dependabot 的提交说明为 "Bump foo from 1.0 to 1.1"，人工提交也可能这样写，只认机器人
+    let is_bump =
+        subject.starts_with("bump ") && subject.contains(" from ") && subject.contains(" to ");
+    if DEPENDENCY_PREFIXES.iter().any(|p| subject.starts_with(p)) || (is_bot && is_bump) {
+        return Some(AutomationKind::Dependency);
+    }
+    if RELEASE_PREFIXES.iter().any(|p| subject.starts_with(p)) {
+        return Some(AutomationKind::Release);
+    }
+    if FORMATTING_PREFIXES.iter().any(|p| subject.starts_with(p)) {
+        return Some(AutomationKind::Formatting);
+    }
+    is_bot.then_some(AutomationKind::Bot)
+}
+
+/// 按 --name-status 的状态列判断是否为批量移动或重命名
+pub(crate) fn is_mass_rename(statuses: &[&str]) -> bool {
+    statuses.len() >= MASS_RENAME_MIN_FILES && statuses.iter().all(|s| s.starts_with('R'))
+}
+
+/// 在作者和说明之外再结合文件列表判断，如批量重命名
+pub(crate) fn flag_automated(commits: &mut [GitCommit]) {
+    for commit in commits.iter_mut() {
+        let statuses: Vec<&str> = commit.files.iter().map(|f| f.status.as_str()).collect();
+        let mass_rename = is_mass_rename(&statuses);
+        commit.automation = detect_automation(&commit.author, &commit.email, &commit.message)
+            .or(mass_rename.then_some(AutomationKind::MassRename));
+    }
+}
+
+/// 项目未开启 include_automated 时，自动化提交不计入统计和报告
+pub(crate) fn automation_excluded(path: &str) -> bool {
+    find_project_by_path(path)
+        .map(|p| !p.include_automated)
+        .unwrap_or(true)
+}
@@ -0,0 +1,354 @@
+use super::dedup::pipe_through;
+use super::git::{classify_failure, git_command, git_features, GitCommit, FIELD_SEPARATOR};
+use super::pull_request::parse_merge_message;
+use std::collections::{HashMap, HashSet};
+
+// 主干分支上的提交不算某个功能分支的工作
+const MAINLINE_BRANCHES: [&str; 5] = ["main", "master", "develop", "trunk", "HEAD"];
+
+/// 推断每个提交最初开发所在的分支，依次参考：
+/// 1. 分支 reflog 中的 `commit:` 记录，说明提交就是在该分支上创建的；
+/// 2. name-rev 路径上把提交合入的合并提交，按合并说明（Merge branch 'x' / Merge pull request #N from y）
+///    归属分支 x / y，嵌套合并时取离提交最近的一次（更接近实际开发分支）；
+/// 3. name-rev 给出的最近分支，排除主干分支，适用于尚未合并的分支
+///
+/// 每一步都只启动固定数量的 git 进程，与提交数和分支数无关
+pub(crate) fn attach_branches(repo_path: &str, commits: &mut [GitCommit]) {
+    if commits.is_empty() {
+        return;
+    }
+    let wanted: HashSet<&str> = commits.iter().map(|c| c.hash.as_str()).collect();
+    let mut branches = reflog_branches(repo_path, &wanted).unwrap_or_else(|e| {
+        eprintln!("读取分支 reflog 失败 ({}): {}", repo_path, e);
+        HashMap::new()
+    });
+
+    let unresolved: Vec<&str> = commits
+        .iter()
+        .map(|c| c.hash.as_str())
+        .filter(|h| !branches.contains_key(*h))
+        .collect();
+    match name_rev_branches(repo_path, &unresolved) {
+        Ok(named) => branches.extend(named),
+        Err(e) => eprintln!("name-rev 执行失败 ({}): {}", repo_path, e),
+    }
+
+    for commit in commits.iter_mut() {
+        commit.branch = branches.get(&commit.hash).cloned();
+    }
+}
+
+/// 本地分支 reflog 中由 commit / commit (amend) / cherry-pick 产生的记录，
+/// 所有分支的 reflog 通过一次 `log -g --stdin` 读取
+fn reflog_branches(
+    repo_path: &str,
+    wanted: &HashSet<&str>,
+) -> Result<HashMap<String, String>, String> {
+    let mut branches = HashMap::new();
+    let output = git_command()
+        .args(["for-each-ref", "--format=%(refname)", "refs/heads/"])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("获取分支列表失败: {}", e))?;
+    if !output.status.success() {
+        return Err(classify_failure("获取分支列表失败", repo_path, &output.stderr).into());
+    }
+    let refs: String = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|r| !is_mainline(branch_of_ref(r)))
+        .map(|r| format!("{}\n", r))
+        .collect();
+    if refs.is_empty() {
+        return Ok(branches);
+    }
+
+    // 没有 reflog 的分支（如克隆后未切换过）只输出一条空说明的记录
+    let format = format!("--format=%gD{sep}%H{sep}%gs", sep = FIELD_SEPARATOR);
+    let reflog = pipe_through(
+        repo_path,
+        &["log", "-g", "--stdin", format.as_str()],
+        refs.into_bytes(),
+    )?;
+    for line in String::from_utf8_lossy(&reflog).lines() {
+        let mut parts = line.splitn(3, FIELD_SEPARATOR);
+        let (Some(selector), Some(hash), Some(subject)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        let created_here = subject.starts_with("commit") || subject.starts_with("cherry-pick");
+        if created_here && wanted.contains(hash) {
+            // refs/heads/feature/x@{3}
+            let reference = selector.rsplit_once("@{").map_or(selector, |(r, _)| r);
+            branches
+                .entry(hash.to_string())
+                .or_insert_with(|| branch_of_ref(reference).to_string());
+        }
+    }
+    Ok(branches)
+}
+
+fn branch_of_ref(reference: &str) -> &str {
+    reference.strip_prefix("refs/heads/").unwrap_or(reference)
+}
+
+/// 从合并提交说明中取出被合并的分支名，PR 格式见 parse_merge_message，其余为 git 的默认格式
+pub(crate) fn merged_branch_name(subject: &str) -> Option<String> {
+    let subject = subject.trim();
+    if let Some(source) = parse_merge_message(subject).and_then(|pr| pr.source_branch) {
+        return Some(source);
+    }
+
+    // Merge branch 'feature/login' into 'main' / Merge remote-tracking branch 'origin/x'
+    let rest = subject
+        .strip_prefix("Merge branch '")
+        .or_else(|| subject.strip_prefix("Merge remote-tracking branch '"))?;
+    let (name, _) = rest.split_once('\'')?;
+    let name = match subject.starts_with("Merge remote-tracking") {
+        true => name.split_once('/').map_or(name, |(_, branch)| branch),
+        false => name,
+    };
+    Some(name.to_string())
+}
+
+fn is_mainline(branch: &str) -> bool {
+    MAINLINE_BRANCHES.contains(&branch) || branch.starts_with("release/")
+}
+
+/// name-rev 给出的名称，形如 `feature/x~2`、`remotes/origin/x^2~1`。
+/// 已进入主干的提交离功能分支可能更近，先用主干命名，只取路径上合并提交对应的分支；
+/// 其余提交再用全部分支命名，路径上没有可用的合并提交时取名称本身的分支
+fn name_rev_branches(repo_path: &str, hashes: &[&str]) -> Result<HashMap<String, String>, String> {
+    let mainline_refs: Vec<String> = MAINLINE_BRANCHES
+        .iter()
+        .filter(|b| **b != "HEAD")
+        .flat_map(|b| [format!("refs/heads/{}", b), format!("refs/remotes/*/{}", b)])
+        .chain(["refs/heads/release/*".to_string()])
+        .collect();
+    let on_mainline = name_rev(repo_path, hashes, &mainline_refs)?;
+    let off_mainline: Vec<&str> = hashes
+        .iter()
+        .copied()
+        .filter(|h| !on_mainline.contains_key(*h))
+        .collect();
+    let all_refs = ["refs/heads/*".to_string(), "refs/remotes/*".to_string()];
+    let elsewhere = name_rev(repo_path, &off_mainline, &all_refs)?;
+
+    let merges: Vec<String> = on_mainline
+        .values()
+        .chain(elsewhere.values())
+        .flat_map(|name| merge_revs(name))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let subjects = commit_subjects(repo_path, &merges)?;
+    let merged_branch = |name: &str| {
+        merge_revs(name)
+            .iter()
+            .filter_map(|merge| merged_branch_name(subjects.get(merge)?))
+            .find(|branch| !is_mainline(branch))
+    };
+
+    let mut branches = HashMap::new();
+    for (hash, name) in on_mainline {
+        if let Some(branch) = merged_branch(&name) {
+            branches.insert(hash, branch);
+        }
+    }
+    for (hash, name) in elsewhere {
+        let branch = merged_branch(&name).or_else(|| {
+            let name = name.split(['~', '^']).next()?;
+            let name = name.strip_prefix("heads/").unwrap_or(name);
+            let name = match name.strip_prefix("remotes/") {
+                Some(remote) => remote.split_once('/')?.1,
+                None => name,
+            };
+            (!is_mainline(name)).then(|| name.to_string())
+        });
+        if let Some(branch) = branch {
+            branches.insert(hash, branch);
+        }
+    }
+    Ok(branches)
+}
+
+/// 名称路径上经过第二个及以后父提交的合并提交，离提交最近的在前：
+/// `main~3^2~1^2~4` -> [`main~3^2~1`, `main~3`]
+fn merge_revs(name: &str) -> Vec<String> {
+    let mut merges: Vec<String> = name
+        .match_indices('^')
+        .filter(|(i, _)| {
+            let parent: String = name[i + 1..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            parent.parse::<u32>().is_ok_and(|n| n >= 2)
+        })
+        .map(|(i, _)| name[..i].to_string())
+        .collect();
+    merges.reverse();
+    merges
+}
+
+// 一次 cat-file --batch 读取各合并提交的标题
+fn commit_subjects(repo_path: &str, revs: &[String]) -> Result<HashMap<String, String>, String> {
+    let mut subjects = HashMap::new();
+    if revs.is_empty() {
+        return Ok(subjects);
+    }
+    let input: String = revs.iter().map(|r| format!("{}\n", r)).collect();
+    let output = pipe_through(repo_path, &["cat-file", "--batch"], input.into_bytes())?;
+
+    // 每个对象输出为 "<oid> <type> <size>\n<内容>\n"，不存在时为 "<name> missing\n"
+    let mut rest = output.as_slice();
+    for rev in revs {
+        let Some(header_end) = rest.iter().position(|&b| b == b'\n') else {
+            break;
+        };
+        let header = String::from_utf8_lossy(&rest[..header_end]).to_string();
+        rest = &rest[header_end + 1..];
+        let Some(size) = header
+            .rsplit(' ')
+            .next()
+            .and_then(|s| s.parse::<usize>().ok())
+        else {
+            continue;
+        };
+        let Some(content) = rest.get(..size) else {
+            break;
+        };
+        // 提交对象的头部与说明之间以空行分隔
+        let content = String::from_utf8_lossy(content);
+        if let Some((_, message)) = content.split_once("\n\n") {
+            subjects.insert(
+                rev.clone(),
+                message.lines().next().unwrap_or("").to_string(),
+            );
+        }
+        rest = rest.get(size + 1..).unwrap_or_default();
+    }
+    Ok(subjects)
+}
+
+// 通过 stdin 传入提交，避免上千个哈希超出命令行长度限制；未能命名的提交原样输出，不保留
+fn name_rev(
+    repo_path: &str,
+    hashes: &[&str],
+    refs: &[String],
+) -> Result<HashMap<String, String>, String> {
+    if hashes.is_empty() {
+        return Ok(HashMap::new());
+    }
+    // --stdin 在 2.35 改名为 --annotate-stdin，新版本使用旧名会输出弃用警告
+    let stdin_flag = match git_features().is_some_and(|f| f.annotate_stdin) {
+        true => "--annotate-stdin",
+        false => "--stdin",
+    };
+    let mut args = vec!["name-rev", "--name-only", stdin_flag];
+    let refs: Vec<String> = refs.iter().map(|r| format!("--refs={}", r)).collect();
+    args.extend(refs.iter().map(String::as_str));
+    let input: String = hashes.iter().map(|h| format!("{}\n", h)).collect();
+    let output = pipe_through(repo_path, &args, input.into_bytes())?;
+
+    let stdout = String::from_utf8_lossy(&output);
+    Ok(hashes
+        .iter()
+        .zip(stdout.lines())
+        .filter(|(hash, name)| *hash != name && *name != "undefined")
+        .map(|(hash, name)| (hash.to_string(), name.to_string()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::TempRepo;
+
+    fn commits(repo: &TempRepo) -> Vec<GitCommit> {
+        let log = String::from_utf8(repo.output(&["log", "--all", "--format=%H%x1e%s"])).unwrap();
+        log.lines()
+            .filter_map(|line| line.split_once('\x1e'))
+            .map(|(hash, message)| GitCommit {
+                hash: hash.to_string(),
+                message: message.to_string(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn branch_of<'a>(commits: &'a [GitCommit], message: &str) -> Option<&'a str> {
+        commits
+            .iter()
+            .find(|c| c.message == message)
+            .and_then(|c| c.branch.as_deref())
+    }
+
+    #[test]
+    fn attributes_merged_commits_to_innermost_merge() {
+        let repo = TempRepo::new("branch-merges");
+        repo.write("a.txt", "a\n");
+        repo.commit("init");
+        let mainline = String::from_utf8(repo.output(&["branch", "--show-current"])).unwrap();
+        let mainline = mainline.trim();
+
+        repo.git(&["checkout", "-q", "-b", "feature/b"]);
+        repo.write("b.txt", "b\n");
+        repo.commit("work on b");
+        repo.git(&["checkout", "-q", "-b", "feature/a"]);
+        repo.write("c.txt", "c\n");
+        repo.commit("work on a");
+        repo.git(&["checkout", "-q", "feature/b"]);
+        repo.git(&[
+            "merge",
+            "-q",
+            "--no-ff",
+            "-m",
+            "Merge branch 'feature/a' into feature/b",
+            "feature/a",
+        ]);
+        repo.git(&["checkout", "-q", mainline]);
+        repo.write("d.txt", "d\n");
+        repo.commit("hotfix");
+        repo.git(&[
+            "merge",
+            "-q",
+            "--no-ff",
+            "-m",
+            "Merge branch 'feature/b'",
+            "feature/b",
+        ]);
+        repo.git(&["branch", "-q", "-D", "feature/a", "feature/b"]);
+
+        let mut commits = commits(&repo);
+        attach_branches(&repo.path(), &mut commits);
+        assert_eq!(branch_of(&commits, "work on a"), Some("feature/a"));
+        assert_eq!(branch_of(&commits, "work on b"), Some("feature/b"));
+        assert_eq!(branch_of(&commits, "hotfix"), None);
+    }
+
+    #[test]
+    fn attributes_unmerged_commits_by_reflog_and_name() {
+        let repo = TempRepo::new("branch-unmerged");
+        repo.write("a.txt", "a\n");
+        repo.commit("init");
+        let mainline = String::from_utf8(repo.output(&["branch", "--show-current"])).unwrap();
+
+        repo.git(&["checkout", "-q", "-b", "wip"]);
+        repo.write("b.txt", "b\n");
+        repo.commit("unnamed work");
+        repo.git(&["reflog", "expire", "--expire=now", "--all"]);
+
+        repo.git(&["checkout", "-q", mainline.trim()]);
+        repo.git(&["checkout", "-q", "-b", "topic"]);
+        repo.write("c.txt", "c\n");
+        repo.commit("topic work");
+        // 之后从 topic 派生的分支不影响 reflog 的归属
+        repo.git(&["checkout", "-q", "-b", "later"]);
+
+        let mut commits = commits(&repo);
+        attach_branches(&repo.path(), &mut commits);
+        assert_eq!(branch_of(&commits, "unnamed work"), Some("wip"));
+        assert_eq!(branch_of(&commits, "topic work"), Some("topic"));
+        assert_eq!(branch_of(&commits, "init"), None);
+    }
+}
@@ -0,0 +1,359 @@
+use super::automation::automation_excluded;
+use super::git::{
+    classify_failure, counted_commits, git_command, parse_commit_date, parse_numstat_z,
+    validate_authors, validate_date, validate_path, FIELD_SEPARATOR,
+};
+use super::repo::{resolve_repo_path, revision_args};
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChurnAnalysis {
+    pub files: Vec<ChurnEntry>,
+    pub directories: Vec<ChurnEntry>,
+    pub hotspots: Vec<HotspotSeries>,
+    pub bucket: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChurnEntry {
+    pub path: String,
+    pub change_count: usize,
+    pub lines_added: usize,
+    pub lines_deleted: usize,
+    pub lines_churned: usize,
+    pub author_count: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HotspotSeries {
+    pub path: String,
+    pub points: Vec<ChurnPoint>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChurnPoint {
+    pub period: String,
+    pub change_count: usize,
+    pub lines_churned: usize,
+}
+
+/// 带行数统计的提交，供 churn 类分析使用
+pub(crate) struct NumstatCommit {
+    pub hash: String,
+    pub email: String,
+    pub date: String,
+    pub files: Vec<NumstatFile>,
+}
+
+pub(crate) struct NumstatFile {
+    pub path: String,
+    pub added: usize,
+    pub deleted: usize,
+}
+
+#[derive(Default)]
+struct ChurnAccumulator {
+    change_count: usize,
+    lines_added: usize,
+    lines_deleted: usize,
+    authors: HashSet<String>,
+}
+
+impl ChurnAccumulator {
+    fn add_lines(&mut self, file: &NumstatFile, email: &str) {
+        self.lines_added += file.added;
+        self.lines_deleted += file.deleted;
+        self.authors.insert(email.to_lowercase());
+    }
+
+    fn into_entry(self, path: String) -> ChurnEntry {
+        ChurnEntry {
+            path,
+            change_count: self.change_count,
+            lines_added: self.lines_added,
+            lines_deleted: self.lines_deleted,
+            lines_churned: self.lines_added + self.lines_deleted,
+            author_count: self.authors.len(),
+        }
+    }
+}
+
+const DEFAULT_LIMIT: usize = 50;
+const HOTSPOT_COUNT: usize = 10;
+
+#[tauri::command]
+pub fn get_churn_analysis(
+    path: String,
+    since: String,
+    until: String,
+    authors: Vec<String>,
+    include_submodules: Vec<String>,
+    bucket: Option<String>,
+    limit: Option<usize>,
+) -> Result<ChurnAnalysis, String> {
+    let repo_path = resolve_repo_path(&path)?;
+    validate_date(&since)?;
+    validate_date(&until)?;
+    validate_authors(&authors)?;
+
+    let bucket = bucket.unwrap_or_else(|| "week".to_string());
+    if !matches!(bucket.as_str(), "day" | "week" | "month") {
+        return Err(format!("不支持的时间粒度: {}", bucket));
+    }
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+
+    let mut commits = fetch_numstat_log(&repo_path, &since, &until, &authors)?;
+
+    for sub_path in include_submodules {
+        if let Err(e) = validate_path(&sub_path) {
+            eprintln!("子模块路径无效 ({}): {}", sub_path, e);
+            continue;
+        }
+        // 子模块文件路径加上相对主仓库的前缀，避免与主仓库同名文件混在一起
+        let prefix = Path::new(&sub_path)
+            .strip_prefix(&path)
+            .map(|rel| rel.to_string_lossy().to_string())
+            .unwrap_or_default();
+        match fetch_numstat_log(&sub_path, &since, &until, &authors) {
+            Ok(mut sub_commits) => {
+                if !prefix.is_empty() {
+                    for commit in sub_commits.iter_mut() {
+                        for file in commit.files.iter_mut() {
+                            file.path = format!("{}/{}", prefix, file.path);
+                        }
+                    }
+                }
+                commits.extend(sub_commits);
+            }
+            Err(e) => eprintln!("子模块日志获取失败 ({}): {}", sub_path, e),
+        }
+    }
+
+    Ok(analyze_churn(&commits, &bucket, limit))
+}
+
+fn analyze_churn(commits: &[NumstatCommit], bucket: &str, limit: usize) -> ChurnAnalysis {
+    let mut files: HashMap<String, ChurnAccumulator> = HashMap::new();
+    let mut directories: HashMap<String, ChurnAccumulator> = HashMap::new();
+
+    for commit in commits {
+        // 目录的变更次数按提交计，同一提交改动目录下多个文件只算一次
+        let mut touched_dirs: HashSet<String> = HashSet::new();
+        for file in &commit.files {
+            let entry = files.entry(file.path.clone()).or_default();
+            entry.change_count += 1;
+            entry.add_lines(file, &commit.email);
+            for dir in parent_dirs(&file.path) {
+                let entry = directories.entry(dir.clone()).or_default();
+                if touched_dirs.insert(dir) {
+                    entry.change_count += 1;
+                }
+                entry.add_lines(file, &commit.email);
+            }
+        }
+    }
+
+    let files = rank_entries(files, limit);
+    let directories = rank_entries(directories, limit);
+
+    let hotspots = files
+        .iter()
+        .take(HOTSPOT_COUNT)
+        .map(|entry| {
+            let mut points: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+            for commit in commits {
+                let period = match period_key(&commit.date, bucket) {
+                    Some(p) => p,
+                    None => continue,
+                };
+                for file in commit.files.iter().filter(|f| f.path == entry.path) {
+                    let point = points.entry(period.clone()).or_default();
+                    point.0 += 1;
+                    point.1 += file.added + file.deleted;
+                }
+            }
+            HotspotSeries {
+                path: entry.path.clone(),
+                points: points
+                    .into_iter()
+                    .map(|(period, (change_count, lines_churned))| ChurnPoint {
+                        period,
+                        change_count,
+                        lines_churned,
+                    })
+                    .collect(),
+            }
+        })
+        .collect();
+
+    ChurnAnalysis {
+        files,
+        directories,
+        hotspots,
+        bucket: bucket.to_string(),
+    }
+}
+
+fn rank_entries(entries: HashMap<String, ChurnAccumulator>, limit: usize) -> Vec<ChurnEntry> {
+    let mut ranked: Vec<ChurnEntry> = entries
+        .into_iter()
+        .map(|(path, acc)| acc.into_entry(path))
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.change_count
+            .cmp(&a.change_count)
+            .then(b.lines_churned.cmp(&a.lines_churned))
+            .then(a.path.cmp(&b.path))
+    });
+    ranked.truncate(limit);
+    ranked
+}
+
+// a/b/c.rs → ["a", "a/b"]
+fn parent_dirs(path: &str) -> Vec<String> {
+    path.match_indices('/')
+        .map(|(idx, _)| path[..idx].to_string())
+        .collect()
+}
+
+/// 按粒度生成时间桶：day → 2024-01-15，week → 2024-W03（ISO 周），month → 2024-01
+pub(crate) fn period_key(date: &str, bucket: &str) -> Option<String> {
+    let dt = parse_commit_date(date)?;
+    let key = match bucket {
+        "day" => dt.format("%Y-%m-%d").to_string(),
+        "month" => dt.format("%Y-%m").to_string(),
+        _ => {
+            let week = dt.iso_week();
+            format!("{}-W{:02}", week.year(), week.week())
+        }
+    };
+    Some(key)
+}
+
+pub(crate) fn fetch_numstat_log(
+    path: &str,
+    since: &str,
+    until: &str,
+    authors: &[String],
+) -> Result<Vec<NumstatCommit>, String> {
+    let format_str = format!(
+        "--pretty=format:{sep}%H{sep}%ae{sep}%ai",
+        sep = FIELD_SEPARATOR
+    );
+
+    let mut args = vec![
+        "log".to_string(),
+        format!("--since={}", since),
+        format!("--until={}", until),
+        format_str,
+        "--numstat".to_string(),
+        "-z".to_string(),           // 非 ASCII 路径保持原样，不加引号转义
+        "--no-renames".to_string(), // 避免 `a => b` 形式的路径干扰统计
+        "--no-merges".to_string(),
+    ];
+
+    for author in authors {
+        args.push(format!("--author={}", author));
+    }
+    args.extend(revision_args(path));
+
+    let output = git_command()
+        .args(&args)
+        .current_dir(path)
+        .output()
+        .map_err(|e| format!("获取 Git 变更统计失败: {}", e))?;
+
+    if !output.status.success() {
+        return Err(classify_failure("Git log 执行失败", path, &output.stderr).into());
+    }
+
+    let mut commits = parse_numstat_log(&output.stdout);
+    // 与提交数口径一致，项目排除自动化提交时去掉机器人、依赖升级、批量重命名等提交
+    if automation_excluded(path) {
+        let counted = counted_commits(path, since, until, authors)?;
+        commits.retain(|c| counted.contains(&c.hash));
+    }
+    Ok(commits)
+}
+
+fn parse_numstat_log(raw: &[u8]) -> Vec<NumstatCommit> {
+    parse_numstat_z(raw)
+        .into_iter()
+        .filter_map(|(header, entries)| {
+            let parts: Vec<&str> = header.splitn(3, FIELD_SEPARATOR).collect();
+            if parts.len() != 3 {
+                return None;
+            }
+            Some(NumstatCommit {
+                hash: parts[0].to_string(),
+                email: parts[1].to_string(),
+                date: parts[2].to_string(),
+                // 二进制文件的行数为 "-"，按 0 处理
+                files: entries
+                    .into_iter()
+                    .map(|e| NumstatFile {
+                        path: e.path,
+                        added: e.added.unwrap_or(0),
+                        deleted: e.deleted.unwrap_or(0),
+                    })
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::{repo_with_automated_commits, TempRepo};
+
+    #[test]
+    fn excludes_automated_commits_from_churn() {
+        let repo = repo_with_automated_commits("churn-automation");
+        let analysis = get_churn_analysis(
+            repo.path(),
+            "2000-01-01".to_string(),
+            "2099-12-31".to_string(),
+            vec![],
+            vec![],
+            None,
+            None,
+        )
+        .unwrap();
+
+        let paths: Vec<&str> = analysis.files.iter().map(|f| f.path.as_str()).collect();
+        assert!(paths.contains(&"src/main.rs"));
+        assert!(!paths.contains(&"Cargo.lock"));
+        assert!(!paths.iter().any(|p| p.starts_with("new/")));
+        assert!(analysis.files.iter().all(|f| f.change_count == 1));
+    }
+
+    #[test]
+    fn keeps_non_ascii_paths_unquoted() {
+        let repo = TempRepo::new("churn-non-ascii");
+        repo.write("订单/服务.rs", "fn a() {}\n");
+        repo.commit("add order service");
+        repo.write("订单/服务.rs", "fn a() {}\nfn b() {}\n");
+        repo.commit("extend order service");
+
+        let analysis = get_churn_analysis(
+            repo.path(),
+            "2000-01-01".to_string(),
+            "2099-12-31".to_string(),
+            vec![],
+            vec![],
+            Some("day".to_string()),
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(analysis.files[0].path, "订单/服务.rs");
+        assert_eq!(analysis.files[0].change_count, 2);
+        assert_eq!(analysis.files[0].lines_added, 2);
+        assert_eq!(analysis.directories[0].path, "订单");
+        assert_eq!(analysis.hotspots[0].path, "订单/服务.rs");
+    }
+}
@@ -0,0 +1,224 @@
+use super::git::{
+    author_filters, classify_failure, component_matcher, count_commits, git_command,
+    parse_fields_numstat_z, validate_authors, validate_date, validate_path, SignatureSummary,
+};
+use super::repo::{resolve_repo_path, revision_args};
+use super::ticket::extract_tickets;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeriodComparison {
+    pub current: PeriodAggregate,
+    pub previous: PeriodAggregate,
+    pub delta: PeriodDelta,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeriodAggregate {
+    pub since: String,
+    pub until: String,
+    pub commit_count: usize,
+    pub lines_added: usize,
+    pub lines_deleted: usize,
+    pub lines_churned: usize,
+    pub files_changed: usize,
+    pub components: Vec<String>,
+    pub tickets: Vec<String>,
+}
+
+/// 本期减上期；变化率以上期为基数，上期为 0 时为空
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PeriodDelta {
+    pub commit_count: i64,
+    pub lines_added: i64,
+    pub lines_deleted: i64,
+    pub lines_churned: i64,
+    pub files_changed: i64,
+    pub component_count: i64,
+    pub ticket_count: i64,
+    pub commit_change_rate: Option<f64>,
+    pub churn_change_rate: Option<f64>,
+    pub new_components: Vec<String>,     // 本期涉及而上期未涉及的模块
+    pub dropped_components: Vec<String>, // 上期涉及而本期未涉及的模块
+}
+
+/// 对比两个时间段的提交统计，如本周与上周、本季度与上季度
+#[tauri::command]
+pub fn get_period_comparison(
+    path: String,
+    since: String,
+    until: String,
+    previous_since: String,
+    previous_until: String,
+    authors: Vec<String>,
+    include_submodules: Vec<String>,
+) -> Result<PeriodComparison, String> {
+    let repo_path = resolve_repo_path(&path)?;
+    for date in [&since, &until, &previous_since, &previous_until] {
+        validate_date(date)?;
+    }
+    validate_authors(&authors)?;
+
+    let mut repos = vec![(path.clone(), repo_path)];
+    for sub_path in include_submodules {
+        if let Err(e) = validate_path(&sub_path) {
+            eprintln!("子模块路径无效 ({}): {}", sub_path, e);
+            continue;
+        }
+        repos.push((sub_path.clone(), sub_path));
+    }
+
+    let current = aggregate_period(&repos, &since, &until, &authors)?;
+    let previous = aggregate_period(&repos, &previous_since, &previous_until, &authors)?;
+    let delta = compute_delta(&current, &previous);
+
+    Ok(PeriodComparison {
+        current,
+        previous,
+        delta,
+    })
+}
+
+/// repos 为 (项目路径, 执行 git 的目录)，第一个是主仓库，失败时直接返回错误
+fn aggregate_period(
+    repos: &[(String, String)],
+    since: &str,
+    until: &str,
+    authors: &[String],
+) -> Result<PeriodAggregate, String> {
+    let mut aggregate = PeriodAggregate {
+        since: since.to_string(),
+        until: until.to_string(),
+        commit_count: 0,
+        lines_added: 0,
+        lines_deleted: 0,
+        lines_churned: 0,
+        files_changed: 0,
+        components: Vec::new(),
+        tickets: Vec::new(),
+    };
+    let mut components = BTreeSet::new();
+
+    for (i, (project_path, repo)) in repos.iter().enumerate() {
+        // 所有指标都来自同一组提交：与 get_git_stats 的口径一致，包含结对署名的提交并排除自动化提交
+        let mut signatures = SignatureSummary::default();
+        let mut automated = 0;
+        let result = count_commits(repo, since, until, authors, &mut signatures, &mut automated)
+            .and_then(|counted| fetch_period_log(repo, since, until, authors, &counted));
+        let commits = match result {
+            Ok(commits) => commits,
+            Err(e) if i == 0 => return Err(e),
+            Err(e) => {
+                eprintln!("子模块日志获取失败 ({}): {}", repo, e);
+                continue;
+            }
+        };
+
+        let (matcher, prefix) = component_matcher(project_path, repo);
+        let mut files = BTreeSet::new();
+        aggregate.commit_count += commits.len();
+        for commit in commits {
+            for ticket in extract_tickets(&commit.message) {
+                if !aggregate.tickets.contains(&ticket) {
+                    aggregate.tickets.push(ticket);
+                }
+            }
+            for (file, added, deleted) in commit.files {
+                aggregate.lines_added += added;
+                aggregate.lines_deleted += deleted;
+                components.extend(matcher.resolve(&file, &prefix));
+                files.insert(file);
+            }
+        }
+        aggregate.files_changed += files.len();
+    }
+
+    aggregate.lines_churned = aggregate.lines_added + aggregate.lines_deleted;
+    aggregate.components = components.into_iter().collect();
+    Ok(aggregate)
+}
+
+struct PeriodCommit {
+    message: String,
+    files: Vec<(String, usize, usize)>,
+}
+
+/// 时间范围内的提交及其 numstat，只保留 counted 中的提交
+fn fetch_period_log(
+    path: &str,
+    since: &str,
+    until: &str,
+    authors: &[String],
+    counted: &HashSet<String>,
+) -> Result<Vec<PeriodCommit>, String> {
+    let mut commits = Vec::new();
+    let mut seen = HashSet::new();
+    for filter in author_filters(path, authors) {
+        let mut args = vec![
+            "log".to_string(),
+            format!("--since={}", since),
+            format!("--until={}", until),
+            "--pretty=format:%H%x00%B%x00".to_string(),
+            "--numstat".to_string(),
+            "-z".to_string(),
+            "--no-renames".to_string(),
+            "--no-merges".to_string(),
+        ];
+        args.extend(filter);
+        args.extend(revision_args(path));
+
+        let output = git_command()
+            .args(&args)
+            .current_dir(path)
+            .output()
+            .map_err(|e| format!("获取 Git 变更统计失败: {}", e))?;
+        if !output.status.success() {
+            return Err(classify_failure("Git log 执行失败", path, &output.stderr).into());
+        }
+
+        for (mut fields, entries) in parse_fields_numstat_z(&output.stdout, 2) {
+            let message = fields.pop().unwrap_or_default();
+            let hash = fields.pop().unwrap_or_default();
+            if !counted.contains(&hash) || !seen.insert(hash) {
+                continue;
+            }
+            // 二进制文件的行数为 "-"，按 0 处理
+            let files = entries
+                .into_iter()
+                .map(|e| (e.path, e.added.unwrap_or(0), e.deleted.unwrap_or(0)))
+                .collect();
+            commits.push(PeriodCommit { message, files });
+        }
+    }
+    Ok(commits)
+}
+
+fn compute_delta(current: &PeriodAggregate, previous: &PeriodAggregate) -> PeriodDelta {
+    let diff = |a: usize, b: usize| a as i64 - b as i64;
+    let change_rate = |a: usize, b: usize| (b > 0).then(|| (a as f64 - b as f64) / b as f64);
+
+    PeriodDelta {
+        commit_count: diff(current.commit_count, previous.commit_count),
+        lines_added: diff(current.lines_added, previous.lines_added),
+        lines_deleted: diff(current.lines_deleted, previous.lines_deleted),
+        lines_churned: diff(current.lines_churned, previous.lines_churned),
+        files_changed: diff(current.files_changed, previous.files_changed),
+        component_count: diff(current.components.len(), previous.components.len()),
+        ticket_count: diff(current.tickets.len(), previous.tickets.len()),
+        commit_change_rate: change_rate(current.commit_count, previous.commit_count),
+        churn_change_rate: change_rate(current.lines_churned, previous.lines_churned),
+        new_components: current
+            .components
+            .iter()
+            .filter(|c| !previous.components.contains(c))
+            .cloned()
+            .collect(),
+        dropped_components: previous
+            .components
+            .iter()
+            .filter(|c| !current.components.contains(c))
+            .cloned()
+            .collect(),
+    }
+}
@@ -0,0 +1,322 @@
+use super::config::ComponentMapping;
+use super::git::{git_command, GitCommit};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComponentSummary {
+    pub name: String,
+    pub commit_count: usize,
+    pub file_count: usize,
+}
+
+// CODEOWNERS 的常见存放位置，按 GitHub 的查找顺序
+const CODEOWNERS_LOCATIONS: [&str; 4] = [
+    ".github/CODEOWNERS",
+    "CODEOWNERS",
+    "docs/CODEOWNERS",
+    ".gitlab/CODEOWNERS",
+];
+
+struct OwnerRule {
+    pattern: String,
+    areas: Vec<String>,
+}
+
+pub struct ComponentMatcher {
+    mappings: Vec<ComponentMapping>,
+    owner_rules: Vec<OwnerRule>,
+}
+
+impl ComponentMatcher {
+    pub fn load(repo_path: &str, mappings: &[ComponentMapping], use_codeowners: bool) -> Self {
+        let owner_rules = if use_codeowners {
+            read_codeowners(repo_path)
+        } else {
+            Vec::new()
+        };
+        Self {
+            mappings: mappings.to_vec(),
+            owner_rules,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mappings.is_empty() && self.owner_rules.is_empty()
+    }
+
+    /// 显式映射优先；都未命中时使用 CODEOWNERS 中最后一条匹配规则（与 GitHub 语义一致）。
+    /// prefix 为子模块相对主仓库的路径：显式映射按主仓库路径书写，CODEOWNERS 按所在仓库路径书写
+    pub fn resolve(&self, path: &str, prefix: &str) -> Vec<String> {
+        let project_path = if prefix.is_empty() {
+            path.to_string()
+        } else {
+            format!("{}/{}", prefix.trim_end_matches('/'), path)
+        };
+
+        let mut areas: Vec<String> = Vec::new();
+        for mapping in &self.mappings {
+            if path_matches(&mapping.pattern, &project_path) && !areas.contains(&mapping.component)
+            {
+                areas.push(mapping.component.clone());
+            }
+        }
+        if !areas.is_empty() {
+            return areas;
+        }
+
+        self.owner_rules
+            .iter()
+            .rev()
+            .find(|rule| path_matches(&rule.pattern, path))
+            .map(|rule| rule.areas.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn tag_commits(&self, commits: &mut [GitCommit], prefix: &str) {
+        if self.is_empty() {
+            return;
+        }
+        for commit in commits.iter_mut() {
+            let mut commit_areas = BTreeSet::new();
+            for file in commit.files.iter_mut() {
+                file.components = self.resolve(&file.path, prefix);
+                commit_areas.extend(file.components.iter().cloned());
+            }
+            commit.components = commit_areas.into_iter().collect();
+        }
+    }
+}
+
+fn read_codeowners(repo_path: &str) -> Vec<OwnerRule> {
+    // 裸仓库和 bundle 镜像没有工作区文件，从 HEAD 中读取
+    let content = CODEOWNERS_LOCATIONS
+        .iter()
+        .find_map(|loc| fs::read_to_string(Path::new(repo_path).join(loc)).ok())
+        .or_else(|| {
+            CODEOWNERS_LOCATIONS
+                .iter()
+                .find_map(|loc| read_from_head(repo_path, loc))
+        });
+
+    match content {
+        Some(content) => parse_codeowners(&content),
+        None => Vec::new(),
+    }
+}
+
+fn read_from_head(repo_path: &str, file: &str) -> Option<String> {
+    let output = git_command()
+        .arg("show")
+        .arg(format!("HEAD:{}", file))
+        .current_dir(repo_path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn parse_codeowners(content: &str) -> Vec<OwnerRule> {
+    let mut rules = Vec::new();
+    let mut section: Option<String> = None;
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        // GitLab 分节语法：[订单中心] @team 或 ^[可选分节]
+        let header = line.trim_start_matches('^');
+        if header.starts_with('[') {
+            if let Some(end) = header.find(']') {
+                section = Some(header[1..end].trim().to_string());
+            }
+            continue;
+        }
+
+        let mut parts = line.split_whitespace();
+        let pattern = match parts.next() {
+            Some(p) => p.to_string(),
+            None => continue,
+        };
+        let owners: Vec<String> = parts
+            .take_while(|p| !p.starts_with('#'))
+            .map(|o| o.trim_start_matches('@').to_string())
+            .collect();
+
+        // 分节名本身就是业务模块，比负责人更适合作为区域名
+        let areas = match &section {
+            Some(name) => vec![name.clone()],
+            None => owners,
+        };
+        if areas.is_empty() {
+            continue;
+        }
+        rules.push(OwnerRule { pattern, areas });
+    }
+
+    rules
+}
+
+/// gitignore 风格匹配：以 `/` 开头或中间含 `/` 的模式锚定在仓库根，
+/// 否则可匹配任意层级；模式命中某个目录时，目录下的所有文件都算命中，
+/// 以 `/` 结尾的模式只匹配目录
+pub(crate) fn path_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return false;
+    }
+
+    let trimmed = pattern.trim_start_matches('/').trim_end_matches('/');
+    let anchored = pattern.starts_with('/') || trimmed.contains('/');
+    let full_pattern = if anchored {
+        trimmed.to_string()
+    } else {
+        format!("**/{}", trimmed)
+    };
+
+    let pattern_segments: Vec<&str> = full_pattern.split('/').collect();
+    let path_segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+
+    // 文件本身是最后一段，只匹配目录时不参与
+    let last = if pattern.ends_with('/') {
+        path_segments.len() - 1
+    } else {
+        path_segments.len()
+    };
+    (1..=last).any(|end| match_segments(&pattern_segments, &path_segments[..end]))
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.first() {
+        None => path.is_empty(),
+        Some(&"**") => (0..=path.len()).any(|skip| match_segments(&pattern[1..], &path[skip..])),
+        Some(seg) => match path.first() {
+            Some(first) if wildcard_match(seg.as_bytes(), first.as_bytes()) => {
+                match_segments(&pattern[1..], &path[1..])
+            }
+            _ => false,
+        },
+    }
+}
+
+fn wildcard_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some(b'*') => (0..=text.len()).any(|skip| wildcard_match(&pattern[1..], &text[skip..])),
+        Some(b'?') => !text.is_empty() && wildcard_match(&pattern[1..], &text[1..]),
+        Some(c) => text.first() == Some(c) && wildcard_match(&pattern[1..], &text[1..]),
+    }
+}
+
+pub fn summarize_components(commits: &[GitCommit]) -> Vec<ComponentSummary> {
+    let mut summary: HashMap<String, (usize, BTreeSet<&str>)> = HashMap::new();
+
+    for commit in commits {
+        for area in &commit.components {
+            summary.entry(area.clone()).or_default().0 += 1;
+        }
+        for file in &commit.files {
+            for area in &file.components {
+                summary
+                    .entry(area.clone())
+                    .or_default()
+                    .1
+                    .insert(&file.path);
+            }
+        }
+    }
+
+    let mut result: Vec<ComponentSummary> = summary
+        .into_iter()
+        .map(|(name, (commit_count, files))| ComponentSummary {
+            name,
+            commit_count,
+            file_count: files.len(),
+        })
+        .collect();
+    result.sort_by(|a, b| {
+        b.commit_count
+            .cmp(&a.commit_count)
+            .then(a.name.cmp(&b.name))
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_gitignore_style_patterns() {
+        let cases = [
+            ("/src/order", "src/order/a.rs", true),
+            ("/src/order", "lib/src/order/a.rs", false),
+            ("docs/*.md", "docs/a.md", true),
+            ("docs/*.md", "site/docs/a.md", false),
+            ("docs/*.md", "docs/sub/a.md", false),
+            ("order", "app/order/a.rs", true),
+            ("*.sql", "db/migrations/001.sql", true),
+            ("**/test/**", "crates/core/test/a.rs", true),
+            ("src/**/*.rs", "src/a/b/c.rs", true),
+            ("src/**/*.rs", "src/c.rs", true),
+            ("src/**/*.rs", "lib/src/c.rs", false),
+            ("build/", "build/out.txt", true),
+            ("build/", "app/build/out.txt", true),
+            ("build/", "build", false),
+            ("build/", "scripts/build", false),
+            ("/", "a.rs", false),
+        ];
+        for (pattern, path, expected) in cases {
+            assert_eq!(
+                path_matches(pattern, path),
+                expected,
+                "{} {}",
+                pattern,
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn codeowners_last_match_wins_and_sections_name_areas() {
+        let content = "\
+# 默认负责人
+*        @all
+/src/    @core @lead   # 注释
+/src/order/ @orders
+
+[支付] @pay-team
+/src/pay/
+";
+        let matcher = ComponentMatcher {
+            mappings: Vec::new(),
+            owner_rules: parse_codeowners(content),
+        };
+
+        assert_eq!(matcher.resolve("README.md", ""), ["all"]);
+        assert_eq!(matcher.resolve("src/main.rs", ""), ["core", "lead"]);
+        assert_eq!(matcher.resolve("src/order/api.rs", ""), ["orders"]);
+        assert_eq!(matcher.resolve("src/pay/wechat.rs", ""), ["支付"]);
+    }
+
+    #[test]
+    fn explicit_mappings_take_precedence_over_codeowners() {
+        let matcher = ComponentMatcher {
+            mappings: vec![ComponentMapping {
+                pattern: "/app/src/order/".to_string(),
+                component: "订单中心".to_string(),
+            }],
+            owner_rules: parse_codeowners("* @all\n"),
+        };
+
+        // 显式映射按主仓库路径书写，子模块中的文件需要加上前缀再匹配
+        assert_eq!(matcher.resolve("src/order/a.rs", "app"), ["订单中心"]);
+        assert_eq!(matcher.resolve("src/order/a.rs", ""), ["all"]);
+    }
+}
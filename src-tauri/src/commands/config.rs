@@ -1,6 +1,8 @@
+use super::git::set_git_executable;
+use super::repo::{common_dir, is_bundle_path};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri_plugin_autostart::ManagerExt;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -13,6 +15,12 @@ pub struct GlobalConfig {
     pub auto_show_ball: bool,
     pub projects: Vec<ProjectConfig>,
     pub first_launch: bool,
+    pub work_sessions: WorkSessionConfig,
+    pub timesheet: TimesheetConfig,
+    pub diff_excerpts: DiffExcerptConfig,
+    pub git_hosts: Vec<GitHostConfig>,
+    pub outliers: OutlierConfig,
+    pub git_path: String, // 为空时使用 PATH 中的 git
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -44,6 +52,23 @@ pub struct ProjectConfig {
     pub repo_path: String,
     pub authors: Vec<String>,
     pub submodules: Vec<SubmoduleConfig>,
+    #[serde(default)]
+    pub components: Vec<ComponentMapping>,
+    #[serde(default = "default_true")]
+    pub use_codeowners: bool,
+    /// 按作者筛选时同时统计 Co-authored-by 尾注中出现该作者的提交
+    #[serde(default = "default_true")]
+    pub count_co_authored: bool,
+    /// 统计和报告中包含机器人、依赖升级、格式化等自动化提交
+    #[serde(default)]
+    pub include_automated: bool,
+}
+
+/// 路径 → 业务模块映射，pattern 使用 gitignore 风格的通配符
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ComponentMapping {
+    pub pattern: String,
+    pub component: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -62,6 +87,60 @@ pub struct LlmConfig {
     pub temperature: f64,
 }
 
+fn default_true() -> bool {
+    true
+}
+
+/// 工时估算参数：相邻提交间隔超过 gap 视为新会话，会话首个提交前补 lead_in 的准备时间
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WorkSessionConfig {
+    pub gap_minutes: u32,
+    pub lead_in_minutes: u32,
+}
+
+/// 工时表导出格式，列的顺序和表头按 HR 系统的导入模板配置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimesheetConfig {
+    pub columns: Vec<TimesheetColumn>,
+    pub date_format: String,
+    pub summary_max_chars: usize,
+}
+
+/// field 取值：date / project / hours / summary / commit_count / constant（固定填 value）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimesheetColumn {
+    pub field: String,
+    pub header: String,
+    #[serde(default)]
+    pub value: String,
+}
+
+/// 写入 prompt 的 diff 片段预算，max_hunk_lines 限制单个 hunk 保留的行数
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiffExcerptConfig {
+    pub token_budget: usize,
+    pub max_hunk_lines: usize,
+}
+
+/// 批量改动（格式化、引入第三方代码等）的判定阈值，
+/// collapse_in_stats 开启时这类提交的文件不计入文件变更统计
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OutlierConfig {
+    pub max_files: usize,
+    pub max_churn: usize,
+    pub collapse_in_stats: bool,
+}
+
+/// 自建代码托管实例：kind 取 github / gitlab / gitea / gitee / bitbucket，
+/// web_url 为空时使用 https://<host>
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitHostConfig {
+    pub host: String,
+    pub kind: String,
+    #[serde(default)]
+    pub web_url: String,
+}
+
 impl Default for GlobalConfig {
     fn default() -> Self {
         Self {
@@ -88,11 +167,56 @@ impl Default for GlobalConfig {
             auto_show_ball: true,
             projects: vec![],
             first_launch: true,
+            work_sessions: WorkSessionConfig {
+                gap_minutes: 120,
+                lead_in_minutes: 30,
+            },
+            timesheet: TimesheetConfig {
+                columns: vec![
+                    TimesheetColumn {
+                        field: "date".to_string(),
+                        header: "日期".to_string(),
+                        value: String::new(),
+                    },
+                    TimesheetColumn {
+                        field: "project".to_string(),
+                        header: "项目".to_string(),
+                        value: String::new(),
+                    },
+                    TimesheetColumn {
+                        field: "hours".to_string(),
+                        header: "工时".to_string(),
+                        value: String::new(),
+                    },
+                    TimesheetColumn {
+                        field: "summary".to_string(),
+                        header: "工作内容".to_string(),
+                        value: String::new(),
+                    },
+                ],
+                date_format: "%Y-%m-%d".to_string(),
+                summary_max_chars: 200,
+            },
+            diff_excerpts: DiffExcerptConfig {
+                token_budget: 1500,
+                max_hunk_lines: 40,
+            },
+            git_hosts: vec![],
+            outliers: OutlierConfig {
+                max_files: 300,
+                max_churn: 10000,
+                collapse_in_stats: true,
+            },
+            git_path: String::new(),
         }
     }
 }
 
-fn get_config_dir() -> Result<PathBuf, String> {
+pub(crate) fn get_config_dir() -> Result<PathBuf, String> {
+    // 测试使用进程独立的临时目录，不读取也不创建开发者本机的配置
+    #[cfg(test)]
+    let config_dir = std::env::temp_dir().join(format!("dev-report-config-{}", std::process::id()));
+    #[cfg(not(test))]
     let config_dir = dirs::config_dir()
         .ok_or("无法获取配置目录")?
         .join("dev-report");
@@ -131,6 +255,7 @@ pub fn load_config() -> Result<GlobalConfig, String> {
     
     let mut config: GlobalConfig = serde_json::from_str(&content)
         .map_err(|e| format!("解析配置文件失败: {}", e))?;
+    set_git_executable(&config.git_path);
     
     // 数据迁移：更新旧的字数配置为新的推荐值
     let mut need_save = false;
@@ -199,9 +324,33 @@ pub fn load_config() -> Result<GlobalConfig, String> {
     Ok(config)
 }
 
+/// 根据仓库路径查找所属项目，子模块路径归属其父项目，
+/// 同一仓库的其他关联工作区也归属到已配置的项目
+pub fn find_project_by_path(path: &str) -> Option<ProjectConfig> {
+    let config = load_config().ok()?;
+    let target = Path::new(path);
+    let by_prefix = config
+        .projects
+        .iter()
+        .filter(|p| !p.repo_path.is_empty() && target.starts_with(&p.repo_path))
+        .max_by_key(|p| p.repo_path.len());
+    if let Some(project) = by_prefix {
+        return Some(project.clone());
+    }
+
+    let target_common = common_dir(path)?;
+    config
+        .projects
+        .into_iter()
+        .find(|p| {
+            !is_bundle_path(&p.repo_path) && common_dir(&p.repo_path).as_ref() == Some(&target_common)
+        })
+}
+
 #[tauri::command]
 pub fn save_config(config: GlobalConfig) -> Result<(), String> {
     let config_path = get_config_file_path()?;
+    set_git_executable(&config.git_path);
     
     let json = serde_json::to_string_pretty(&config)
         .map_err(|e| format!("序列化配置失败: {}", e))?;
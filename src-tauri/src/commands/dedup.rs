@@ -0,0 +1,408 @@
+use super::git::{classify_failure, git_command, GitCommit};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::iter::Peekable;
+use std::process::Stdio;
+
+/// 同一改动的一个落点：cherry-pick 到其他分支或出现在另一个仓库中的副本
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommitLocation {
+    pub hash: String,
+    pub repo: String,
+    pub date: String,
+}
+
+/// 同一批提交的正向和反向 patch-id，提交哈希 -> patch-id
+#[derive(Default)]
+pub(crate) struct PatchIds {
+    pub forward: HashMap<String, String>,
+    pub reverse: HashMap<String, String>,
+}
+
+/// 标注提交的 patch-id，内容相同的改动得到相同的 patch-id；返回的反向 patch-id 供回滚配对使用
+pub(crate) fn attach_patch_ids(repo_path: &str, commits: &mut [GitCommit]) -> PatchIds {
+    if commits.is_empty() {
+        return PatchIds::default();
+    }
+    let hashes: Vec<&str> = commits.iter().map(|c| c.hash.as_str()).collect();
+    match compute_patch_ids(repo_path, &hashes) {
+        Ok(ids) => {
+            for commit in commits.iter_mut() {
+                commit.patch_id = ids.forward.get(&commit.hash).cloned();
+            }
+            ids
+        }
+        Err(e) => {
+            eprintln!("计算 patch-id 失败 ({}): {}", repo_path, e);
+            PatchIds::default()
+        }
+    }
+}
+
+/// diff-tree 只运行一次，反向补丁由正向补丁直接翻转得到。
+/// 回滚提交的反向 patch-id 与被回滚提交的 patch-id 相同
+pub(crate) fn compute_patch_ids(repo_path: &str, hashes: &[&str]) -> Result<PatchIds, String> {
+    if hashes.is_empty() {
+        return Ok(PatchIds::default());
+    }
+    let input: String = hashes.iter().map(|h| format!("{}\n", h)).collect();
+    let patches = pipe_through(
+        repo_path,
+        &["diff-tree", "--stdin", "--root", "-p"],
+        input.into_bytes(),
+    )?;
+    let reversed = reverse_patches(&patches);
+    Ok(PatchIds {
+        forward: patch_ids(repo_path, patches)?,
+        reverse: patch_ids(repo_path, reversed)?,
+    })
+}
+
+// --stable 与行序无关，和 cherry-pick 的判断方式一致
+fn patch_ids(repo_path: &str, patches: Vec<u8>) -> Result<HashMap<String, String>, String> {
+    let output = pipe_through(repo_path, &["patch-id", "--stable"], patches)?;
+    Ok(String::from_utf8_lossy(&output)
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(patch_id, hash)| (hash.to_string(), patch_id.to_string()))
+        .collect())
+}
+
+/// 翻转 `diff-tree -p` 的输出，得到与回滚提交相同的补丁：
+/// 交换文件头和 hunk 头中的新旧两侧，每段连续改动中新增行改为删除行并排在前面
+fn reverse_patches(patches: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(patches.len());
+    let mut lines = patches.split_inclusive(|&b| b == b'\n').peekable();
+    while let Some(line) = lines.next() {
+        if let Some(hunk) = HunkHeader::parse(line) {
+            out.extend(format!("@@ -{} +{} @@", hunk.new, hunk.old).as_bytes());
+            out.extend(hunk.tail);
+            reverse_hunk(&mut lines, hunk.old_lines, hunk.new_lines, &mut out);
+        } else if let Some(old) = line.strip_prefix(b"--- ") {
+            match lines.next_if(|l| l.starts_with(b"+++ ")) {
+                Some(new) => {
+                    out.extend(b"--- ");
+                    out.extend(side_path(&new[4..], b"a/"));
+                    out.extend(b"+++ ");
+                    out.extend(side_path(old, b"b/"));
+                }
+                None => out.extend(line),
+            }
+        } else if let Some(old) = line.strip_prefix(b"old mode ") {
+            match lines.next_if(|l| l.starts_with(b"new mode ")) {
+                Some(new) => {
+                    out.extend(b"old mode ");
+                    out.extend(&new[9..]);
+                    out.extend(b"new mode ");
+                    out.extend(old);
+                }
+                None => out.extend(line),
+            }
+        } else if let Some(mode) = line.strip_prefix(b"new file mode ") {
+            out.extend(b"deleted file mode ");
+            out.extend(mode);
+        } else if let Some(mode) = line.strip_prefix(b"deleted file mode ") {
+            out.extend(b"new file mode ");
+            out.extend(mode);
+        } else {
+            let reversed = reverse_index_line(line)
+                .or_else(|| swap_paths(line, "diff --git ", " ", ""))
+                .or_else(|| swap_paths(line, "Binary files ", " and ", " differ"));
+            out.extend(reversed.unwrap_or_else(|| line.to_vec()));
+        }
+    }
+    out
+}
+
+/// 按 hunk 头记录的行数读取，避免把下一个文件的 `--- a/...` 当作删除行
+fn reverse_hunk<'a>(
+    lines: &mut Peekable<impl Iterator<Item = &'a [u8]>>,
+    mut old_left: usize,
+    mut new_left: usize,
+    out: &mut Vec<u8>,
+) {
+    let mut removed: Vec<&[u8]> = Vec::new();
+    let mut added: Vec<&[u8]> = Vec::new();
+    while old_left > 0 || new_left > 0 {
+        let Some(line) = lines.next() else {
+            break;
+        };
+        let target = match line.first() {
+            Some(b'-') => {
+                old_left = old_left.saturating_sub(1);
+                &mut removed
+            }
+            Some(b'+') => {
+                new_left = new_left.saturating_sub(1);
+                &mut added
+            }
+            _ => {
+                old_left = old_left.saturating_sub(1);
+                new_left = new_left.saturating_sub(1);
+                flush_change(out, &mut removed, &mut added);
+                out.extend(line);
+                while let Some(note) = lines.next_if(|l| l.starts_with(b"\\")) {
+                    out.extend(note);
+                }
+                continue;
+            }
+        };
+        target.push(line);
+        // `\ No newline at end of file` 跟随在它所说明的行之后
+        while let Some(note) = lines.next_if(|l| l.starts_with(b"\\")) {
+            target.push(note);
+        }
+    }
+    flush_change(out, &mut removed, &mut added);
+}
+
+fn flush_change<'a>(out: &mut Vec<u8>, removed: &mut Vec<&'a [u8]>, added: &mut Vec<&'a [u8]>) {
+    for (lines, sign) in [(added, b'-'), (removed, b'+')] {
+        for line in lines.drain(..) {
+            if matches!(line.first(), Some(b'+') | Some(b'-')) {
+                out.push(sign);
+                out.extend(&line[1..]);
+            } else {
+                out.extend(line);
+            }
+        }
+    }
+}
+
+/// 交换 `<prefix><旧路径><sep><新路径><suffix>` 中的两个路径。不检测重命名时两侧路径
+/// 去掉前缀后相同（或一侧为 /dev/null），据此在路径本身包含分隔符时确定拆分位置
+fn swap_paths(line: &[u8], prefix: &str, sep: &str, suffix: &str) -> Option<Vec<u8>> {
+    let text = std::str::from_utf8(line.strip_prefix(prefix.as_bytes())?).ok()?;
+    let body = text.trim_end_matches('\n').strip_suffix(suffix)?;
+    let newline = &text[body.len() + suffix.len()..];
+    body.match_indices(sep).find_map(|(i, _)| {
+        let (old, new) = (&body[..i], &body[i + sep.len()..]);
+        let paired = old == "/dev/null" || new == "/dev/null" || old.get(2..) == new.get(2..);
+        paired.then(|| {
+            [
+                prefix.as_bytes(),
+                &side_path(new.as_bytes(), b"a/"),
+                sep.as_bytes(),
+                &side_path(old.as_bytes(), b"b/"),
+                suffix.as_bytes(),
+                newline.as_bytes(),
+            ]
+            .concat()
+        })
+    })
+}
+
+/// 翻转后旧文件一侧用 a/ 前缀、新文件一侧用 b/ 前缀，与真实的回滚提交一致。
+/// patch-id 会把前缀计算在内，`diff-tree -R` 保留原前缀，得到的 patch-id 与回滚提交对不上
+fn side_path(path: &[u8], prefix: &[u8]) -> Vec<u8> {
+    match path.get(..2) {
+        Some(b"a/") | Some(b"b/") => [prefix, &path[2..]].concat(),
+        _ => path.to_vec(),
+    }
+}
+
+// index <旧 blob>..<新 blob> [mode]
+fn reverse_index_line(line: &[u8]) -> Option<Vec<u8>> {
+    let rest = std::str::from_utf8(line.strip_prefix(b"index ")?).ok()?;
+    let (range, mode) = rest.split_at(rest.find([' ', '\n']).unwrap_or(rest.len()));
+    let (old, new) = range.split_once("..")?;
+    Some(format!("index {}..{}{}", new, old, mode).into_bytes())
+}
+
+/// `@@ -a,b +c,d @@ 上下文`，b、d 省略时为 1
+struct HunkHeader<'a> {
+    old: &'a str,
+    new: &'a str,
+    old_lines: usize,
+    new_lines: usize,
+    tail: &'a [u8],
+}
+
+impl<'a> HunkHeader<'a> {
+    fn parse(line: &'a [u8]) -> Option<Self> {
+        let rest = line.strip_prefix(b"@@ -")?;
+        let end = rest.windows(3).position(|w| w == b" @@")?;
+        let (old, new) = std::str::from_utf8(&rest[..end]).ok()?.split_once(" +")?;
+        let count = |range: &str| match range.split_once(',') {
+            Some((_, count)) => count.parse().ok(),
+            None => Some(1),
+        };
+        Some(Self {
+            old,
+            new,
+            old_lines: count(old)?,
+            new_lines: count(new)?,
+            tail: &rest[end + 3..],
+        })
+    }
+}
+
+pub(crate) fn pipe_through(repo_path: &str, args: &[&str], input: Vec<u8>) -> Result<Vec<u8>, String> {
+    let mut child = git_command()
+        .args(args)
+        .current_dir(repo_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("执行 git {} 失败: {}", args[0], e))?;
+
+    // 在单独线程写入，避免输出缓冲区写满时双方互相等待
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| format!("执行 git {} 失败: 无法写入 stdin", args[0]))?;
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("执行 git {} 失败: {}", args[0], e))?;
+    let _ = writer.join();
+    if !output.status.success() {
+        let context = format!("执行 git {} 失败", args[0]);
+        return Err(classify_failure(&context, repo_path, &output.stderr).into());
+    }
+    Ok(output.stdout)
+}
+
+/// 合并同一改动的多个副本：优先按 Change-Id（Gerrit）识别，其次按 patch-id。
+/// 保留最先出现的提交（主仓库在前），所有副本记录在 landed 中
+pub(crate) fn dedup_commits(commits: Vec<(String, GitCommit)>) -> Vec<GitCommit> {
+    let mut kept: Vec<GitCommit> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for (repo, mut commit) in commits {
+        commit.repo = repo.clone();
+        let location = CommitLocation {
+            hash: commit.hash.clone(),
+            repo,
+            date: commit.date.clone(),
+        };
+        let key = match (&commit.change_id, &commit.patch_id) {
+            (Some(change_id), _) => format!("change:{}", change_id),
+            (None, Some(patch_id)) => format!("patch:{}", patch_id),
+            // 空提交没有 patch-id，无法判断是否重复
+            (None, None) => {
+                kept.push(commit);
+                continue;
+            }
+        };
+
+        match index.get(&key) {
+            Some(&i) => {
+                // 同一仓库中同一提交经多个引用到达时不算新的落点
+                let existing = &mut kept[i];
+                if !existing
+                    .landed
+                    .iter()
+                    .any(|l| l.hash == location.hash && l.repo == location.repo)
+                {
+                    existing.landed.push(location);
+                }
+            }
+            None => {
+                commit.landed = vec![location];
+                index.insert(key, kept.len());
+                kept.push(commit);
+            }
+        }
+    }
+
+    // 只有一个落点时不需要列出
+    for commit in kept.iter_mut() {
+        if commit.landed.len() < 2 {
+            commit.landed.clear();
+        }
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::TempRepo;
+
+    #[test]
+    fn reversed_patches_match_revert_commits() {
+        let repo = TempRepo::new("reverse-patch");
+        let lines: String = (1..=12).map(|i| format!("line {}\n", i)).collect();
+        repo.write("a.txt", &lines);
+        repo.write("b.txt", "-- removed later\n--- looks like a header\n");
+        repo.write("noeol.txt", "first\nlast");
+        repo.write("image.bin", [0u8, 1, 2, 3, 255]);
+        repo.write("run.sh", "echo hi\n");
+        repo.commit("init");
+
+        let changed = lines
+            .replace("line 2\n", "line two\nline 2.5\n")
+            .replace("line 5\n", "")
+            .replace("line 11\n", "line 11\nextra\n");
+        repo.write("a.txt", changed);
+        repo.write("b.txt", "++ added\n");
+        repo.write("noeol.txt", "first\nchanged");
+        repo.write("image.bin", [9u8, 0, 8]);
+        repo.write("empty.txt", "");
+        repo.git(&["update-index", "--chmod=+x", "run.sh"]);
+        repo.commit("edit");
+
+        repo.git(&["rm", "-q", "b.txt", "image.bin"]);
+        repo.write("noeol.txt", "first\nchanged\n");
+        repo.commit("remove");
+
+        // 真实的回滚提交作为对照：每个提交的反向 patch-id 应等于其回滚提交的 patch-id
+        let originals = repo.rev_list("HEAD");
+        for hash in &originals[..2] {
+            repo.git(&["revert", "--no-edit", hash]);
+        }
+        let reverts = repo.rev_list("HEAD~2..HEAD");
+
+        let all: Vec<&str> = originals
+            .iter()
+            .chain(&reverts)
+            .map(String::as_str)
+            .collect();
+        let ids = compute_patch_ids(&repo.path(), &all).unwrap();
+        assert_eq!(ids.forward.len(), 5);
+        // rev-list 倒序：reverts[1] 回滚 originals[0]，reverts[0] 回滚 originals[1]
+        assert_eq!(ids.reverse[&originals[0]], ids.forward[&reverts[1]]);
+        assert_eq!(ids.reverse[&originals[1]], ids.forward[&reverts[0]]);
+        assert_eq!(ids.reverse[&reverts[1]], ids.forward[&originals[0]]);
+    }
+
+    #[test]
+    fn revert_patch_id_matches_original() {
+        let repo = TempRepo::new("revert-patch-id");
+        repo.write("a.txt", "one\ntwo\n");
+        repo.commit("init");
+        repo.write("a.txt", "one\n2\nthree\n");
+        repo.commit("change");
+        repo.write("a.txt", "one\ntwo\n");
+        repo.commit("undo by hand");
+
+        let commits = repo.rev_list("HEAD");
+        let all: Vec<&str> = commits.iter().map(String::as_str).collect();
+        let ids = compute_patch_ids(&repo.path(), &all).unwrap();
+        assert_eq!(ids.reverse[&commits[0]], ids.forward[&commits[1]]);
+    }
+
+    #[test]
+    fn keeps_repo_of_first_copy() {
+        let commit = |hash: &str, patch_id: &str| GitCommit {
+            hash: hash.to_string(),
+            patch_id: Some(patch_id.to_string()),
+            ..Default::default()
+        };
+        let kept = dedup_commits(vec![
+            ("app".to_string(), commit("a1", "p1")),
+            ("app/lib".to_string(), commit("b1", "p1")),
+            ("app/lib".to_string(), commit("b2", "p2")),
+        ]);
+
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].hash, "a1");
+        assert_eq!(kept[0].repo, "app");
+        assert_eq!(kept[0].landed.len(), 2);
+        assert_eq!(kept[1].repo, "app/lib");
+    }
+}
@@ -0,0 +1,626 @@
+use super::config::load_config;
+use super::git::{
+    author_filters, classify_failure, git_command, validate_authors, validate_date, validate_path,
+    FIELD_SEPARATOR,
+};
+use super::repo::{resolve_repo_path, revision_args};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiffExcerptReport {
+    pub excerpts: Vec<DiffExcerpt>,
+    pub token_budget: usize,
+    pub used_tokens: usize,
+    pub candidate_count: usize,
+    pub text: String, // 可直接拼入 prompt 的文本
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiffExcerpt {
+    pub hash: String,
+    pub message: String,
+    pub path: String,
+    pub kind: ExcerptKind,
+    pub content: String,
+    pub tokens: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExcerptKind {
+    Signature,   // 函数/类型签名变化
+    NewFunction, // 新增的函数或类型
+    Test,        // 新增或修改的测试
+    Change,
+}
+
+impl ExcerptKind {
+    fn score(self) -> usize {
+        match self {
+            ExcerptKind::Signature => 4,
+            ExcerptKind::NewFunction => 3,
+            ExcerptKind::Test => 2,
+            ExcerptKind::Change => 1,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            ExcerptKind::Signature => "签名变更",
+            ExcerptKind::NewFunction => "新增定义",
+            ExcerptKind::Test => "测试",
+            ExcerptKind::Change => "改动",
+        }
+    }
+}
+
+struct Hunk {
+    commit: usize,
+    path: String,
+    header: String,
+    lines: Vec<String>,
+}
+
+struct PatchCommit {
+    hash: String,
+    message: String,
+}
+
+// 单个提交最多选取的片段数，避免一个大提交占满预算
+const MAX_EXCERPTS_PER_COMMIT: usize = 3;
+// 参与挑选的提交上限，防止长周期报告生成过大的 patch
+const MAX_PATCH_COMMITS: usize = 300;
+
+// 锁文件、构建产物、压缩文件和代码生成结果对理解工作内容没有帮助
+const GENERATED_FILE_NAMES: [&str; 10] = [
+    "package-lock.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "Cargo.lock",
+    "go.sum",
+    "poetry.lock",
+    "composer.lock",
+    "Gemfile.lock",
+    "Podfile.lock",
+    "uv.lock",
+];
+const GENERATED_SUFFIXES: [&str; 9] = [
+    ".min.js",
+    ".min.css",
+    ".map",
+    ".pb.go",
+    "_pb2.py",
+    ".g.dart",
+    ".freezed.dart",
+    ".snap",
+    ".svg",
+];
+const GENERATED_DIRS: [&str; 7] = [
+    "node_modules/",
+    "vendor/",
+    "dist/",
+    "build/",
+    "target/",
+    "generated/",
+    "__generated__/",
+];
+const GENERATED_MARKERS: [&str; 4] = [
+    "@generated",
+    "DO NOT EDIT",
+    "auto-generated",
+    "Code generated",
+];
+
+// 定义前可能出现的修饰词，剥离后再判断首个关键字
+const DEFINITION_MODIFIERS: [&str; 14] = [
+    "pub",
+    "pub(crate)",
+    "pub(super)",
+    "export",
+    "default",
+    "async",
+    "static",
+    "public",
+    "private",
+    "protected",
+    "abstract",
+    "final",
+    "override",
+    "unsafe",
+];
+const DEFINITION_KEYWORDS: [&str; 11] = [
+    "fn",
+    "def",
+    "func",
+    "function",
+    "class",
+    "interface",
+    "struct",
+    "enum",
+    "trait",
+    "impl",
+    "type",
+];
+const TEST_MARKERS: [&str; 3] = ["#[test]", "@Test", "def test_"];
+// JS 测试框架的用例函数，只在标识符边界处匹配，避免命中 submit(、init( 等调用
+const TEST_CALLS: [&str; 3] = ["describe", "it", "test"];
+
+#[tauri::command]
+pub fn get_diff_excerpts(
+    path: String,
+    since: String,
+    until: String,
+    authors: Vec<String>,
+    include_submodules: Vec<String>,
+    token_budget: Option<usize>,
+) -> Result<DiffExcerptReport, String> {
+    let repo_path = resolve_repo_path(&path)?;
+    validate_date(&since)?;
+    validate_date(&until)?;
+    validate_authors(&authors)?;
+
+    let settings = load_config()?.diff_excerpts;
+    let budget = token_budget.unwrap_or(settings.token_budget);
+
+    let mut commits = Vec::new();
+    let mut hunks = Vec::new();
+    fetch_patch_log(
+        &repo_path,
+        &since,
+        &until,
+        &authors,
+        &mut commits,
+        &mut hunks,
+    )?;
+    for sub_path in include_submodules {
+        if let Err(e) = validate_path(&sub_path) {
+            eprintln!("子模块路径无效 ({}): {}", sub_path, e);
+            continue;
+        }
+        if let Err(e) = fetch_patch_log(
+            &sub_path,
+            &since,
+            &until,
+            &authors,
+            &mut commits,
+            &mut hunks,
+        ) {
+            eprintln!("子模块 diff 获取失败 ({}): {}", sub_path, e);
+        }
+    }
+
+    let mut candidates: Vec<(usize, DiffExcerpt)> = hunks
+        .into_iter()
+        .filter(|h| !is_generated(&h.path, &h.lines))
+        .filter(|h| h.lines.iter().any(|l| is_meaningful_change(l)))
+        .map(|h| {
+            let kind = classify_hunk(&h.path, &h.lines);
+            let commit = &commits[h.commit];
+            let content = render_hunk(&h, settings.max_hunk_lines);
+            let tokens = estimate_tokens(&content) + estimate_tokens(&h.path);
+            (
+                h.commit,
+                DiffExcerpt {
+                    hash: commit.hash.clone(),
+                    message: commit.message.clone(),
+                    path: h.path,
+                    kind,
+                    content,
+                    tokens,
+                },
+            )
+        })
+        .collect();
+    let candidate_count = candidates.len();
+
+    // 优先级高的在前，同级时短片段优先，能在预算内容纳更多信息
+    candidates.sort_by_key(|(_, e)| (Reverse(e.kind.score()), e.tokens));
+
+    let mut used_tokens = 0;
+    let mut per_commit: HashMap<usize, usize> = HashMap::new();
+    let mut selected: Vec<(usize, DiffExcerpt)> = Vec::new();
+    for (commit, excerpt) in candidates {
+        let taken = per_commit.entry(commit).or_insert(0);
+        if *taken >= MAX_EXCERPTS_PER_COMMIT || used_tokens + excerpt.tokens > budget {
+            continue;
+        }
+        *taken += 1;
+        used_tokens += excerpt.tokens;
+        selected.push((commit, excerpt));
+    }
+
+    // 输出按提交顺序组织，同一提交的片段放在一起
+    selected.sort_by_key(|(commit, _)| *commit);
+    let text = render_text(&selected);
+
+    Ok(DiffExcerptReport {
+        excerpts: selected.into_iter().map(|(_, e)| e).collect(),
+        token_budget: budget,
+        used_tokens,
+        candidate_count,
+        text,
+    })
+}
+
+// 作者本人和结对署名各查询一次，与统计口径一致；两次都命中的提交只保留一份
+fn fetch_patch_log(
+    path: &str,
+    since: &str,
+    until: &str,
+    authors: &[String],
+    commits: &mut Vec<PatchCommit>,
+    hunks: &mut Vec<Hunk>,
+) -> Result<(), String> {
+    let mut seen: HashSet<String> = commits.iter().map(|c| c.hash.clone()).collect();
+    for filter in author_filters(path, authors) {
+        let mut args = vec![
+            "log".to_string(),
+            format!("--since={}", since),
+            format!("--until={}", until),
+            format!("--pretty=format:{}%H{}%s", FIELD_SEPARATOR, FIELD_SEPARATOR),
+            "--patch".to_string(),
+            "--unified=1".to_string(),
+            "--no-textconv".to_string(),
+            "--no-ext-diff".to_string(),
+            "--no-color".to_string(),
+            "--no-renames".to_string(),
+            "--ignore-all-space".to_string(), // 纯空白改动不产生 hunk
+            "--ignore-blank-lines".to_string(),
+            "--no-merges".to_string(),
+            format!("--max-count={}", MAX_PATCH_COMMITS),
+        ];
+        args.extend(filter);
+        args.extend(revision_args(path));
+
+        // 非 ASCII 路径原样输出，与 query_git_log 展示的路径一致
+        let output = git_command()
+            .args(["-c", "core.quotePath=false"])
+            .args(&args)
+            .current_dir(path)
+            .output()
+            .map_err(|e| format!("获取 Git diff 失败: {}", e))?;
+
+        if !output.status.success() {
+            return Err(classify_failure("Git log 执行失败", path, &output.stderr).into());
+        }
+
+        let mut pass_commits = Vec::new();
+        let mut pass_hunks = Vec::new();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        parse_patch_log(&stdout, &mut pass_commits, &mut pass_hunks);
+
+        // 本轮提交追加到末尾后的下标，已收录的提交映射为 None 并丢弃其 hunk
+        let indexes: Vec<Option<usize>> = pass_commits
+            .into_iter()
+            .map(|commit| {
+                seen.insert(commit.hash.clone()).then(|| {
+                    commits.push(commit);
+                    commits.len() - 1
+                })
+            })
+            .collect();
+        hunks.extend(pass_hunks.into_iter().filter_map(|mut hunk| {
+            hunk.commit = indexes[hunk.commit]?;
+            Some(hunk)
+        }));
+    }
+    Ok(())
+}
+
+fn parse_patch_log(raw: &str, commits: &mut Vec<PatchCommit>, hunks: &mut Vec<Hunk>) {
+    let mut current_path: Option<String> = None;
+    let mut current_hunk: Option<Hunk> = None;
+
+    for line in raw.lines() {
+        if let Some(header) = line.strip_prefix(FIELD_SEPARATOR) {
+            hunks.extend(current_hunk.take());
+            current_path = None;
+            if let Some((hash, message)) = header.split_once(FIELD_SEPARATOR) {
+                commits.push(PatchCommit {
+                    hash: hash.to_string(),
+                    message: message.trim().to_string(),
+                });
+            }
+        } else if line.starts_with("diff --git ") {
+            hunks.extend(current_hunk.take());
+            current_path = None;
+        } else if line.starts_with("@@") {
+            hunks.extend(current_hunk.take());
+            if let (Some(path), false) = (&current_path, commits.is_empty()) {
+                current_hunk = Some(Hunk {
+                    commit: commits.len() - 1,
+                    path: path.clone(),
+                    header: line.to_string(),
+                    lines: Vec::new(),
+                });
+            }
+        } else if let Some(ref mut hunk) = current_hunk {
+            if line.starts_with(['+', '-', ' ']) {
+                hunk.lines.push(line.to_string());
+            }
+        } else if let Some(path) = line.strip_prefix("+++ ") {
+            // 文件头只出现在 hunk 之前；删除的文件取 `--- a/...` 中的路径
+            if path != "/dev/null" {
+                current_path = Some(strip_diff_prefix(path, "b/"));
+            }
+        } else if let Some(path) = line.strip_prefix("--- ") {
+            if path != "/dev/null" {
+                current_path = Some(strip_diff_prefix(path, "a/"));
+            }
+        }
+    }
+
+    hunks.extend(current_hunk);
+}
+
+// 含空格的路径后面带一个制表符；含引号、反斜杠或控制字符的路径即使关闭 quotePath 也会加引号转义
+fn strip_diff_prefix(path: &str, prefix: &str) -> String {
+    let path = path.strip_suffix('\t').unwrap_or(path);
+    let path = match path.strip_prefix('"').and_then(|p| p.strip_suffix('"')) {
+        Some(quoted) => unquote_c_style(quoted),
+        None => path.to_string(),
+    };
+    path.strip_prefix(prefix).unwrap_or(&path).to_string()
+}
+
+// git 的 C 风格转义：\\、\"、\t、\n 等，非 ASCII 字节写成三位八进制
+fn unquote_c_style(quoted: &str) -> String {
+    let mut bytes = Vec::with_capacity(quoted.len());
+    let mut rest = quoted.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        rest = tail;
+        if byte != b'\\' {
+            bytes.push(byte);
+            continue;
+        }
+        let Some((&escaped, tail)) = rest.split_first() else {
+            bytes.push(byte);
+            break;
+        };
+        rest = tail;
+        match escaped {
+            b'a' => bytes.push(0x07),
+            b'b' => bytes.push(0x08),
+            b't' => bytes.push(b'\t'),
+            b'n' => bytes.push(b'\n'),
+            b'v' => bytes.push(0x0b),
+            b'f' => bytes.push(0x0c),
+            b'r' => bytes.push(b'\r'),
+            b'0'..=b'7'
+                if rest.len() >= 2 && rest[..2].iter().all(|b| (b'0'..=b'7').contains(b)) =>
+            {
+                let octal = [escaped, rest[0], rest[1]];
+                let value = octal
+                    .iter()
+                    .fold(0u32, |acc, d| acc * 8 + (d - b'0') as u32);
+                bytes.push(value as u8);
+                rest = &rest[2..];
+            }
+            other => bytes.push(other),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn is_generated(path: &str, lines: &[String]) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    if GENERATED_FILE_NAMES.contains(&file_name)
+        || GENERATED_SUFFIXES.iter().any(|s| file_name.ends_with(s))
+    {
+        return true;
+    }
+    let with_slash = format!("/{}", path);
+    if GENERATED_DIRS
+        .iter()
+        .any(|d| with_slash.contains(&format!("/{}", d)))
+    {
+        return true;
+    }
+    lines
+        .iter()
+        .take(10)
+        .any(|l| GENERATED_MARKERS.iter().any(|m| l.contains(m)))
+}
+
+fn is_meaningful_change(line: &str) -> bool {
+    line.starts_with(['+', '-']) && !line[1..].trim().is_empty()
+}
+
+fn is_definition(code: &str) -> bool {
+    let mut words = code.split_whitespace().peekable();
+    while let Some(word) = words.peek() {
+        if DEFINITION_MODIFIERS.contains(word) {
+            words.next();
+        } else {
+            break;
+        }
+    }
+    match words.next() {
+        Some(word) => {
+            let keyword = word.split(['(', '<', '{']).next().unwrap_or(word);
+            DEFINITION_KEYWORDS.contains(&keyword)
+                // const handler = (...) => {...}
+                || (matches!(keyword, "const" | "let") && code.contains("=>"))
+        }
+        None => false,
+    }
+}
+
+fn is_test_path(path: &str) -> bool {
+    let lower = path.to_lowercase();
+    let file_name = lower.rsplit('/').next().unwrap_or(&lower);
+    lower.contains("/tests/")
+        || lower.starts_with("tests/")
+        || lower.contains("__tests__/")
+        || file_name.starts_with("test_")
+        || file_name.contains("_test.")
+        || file_name.contains(".test.")
+        || file_name.contains(".spec.")
+}
+
+fn has_test_marker(code: &str) -> bool {
+    TEST_MARKERS.iter().any(|m| code.contains(m))
+        || TEST_CALLS.iter().any(|name| {
+            let call = format!("{}(", name);
+            code.match_indices(&call).any(|(start, _)| {
+                !code[..start]
+                    .chars()
+                    .next_back()
+                    .is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '.' || c == '$')
+            })
+        })
+}
+
+fn classify_hunk(path: &str, lines: &[String]) -> ExcerptKind {
+    let added_defs = lines
+        .iter()
+        .filter(|l| l.starts_with('+') && is_definition(&l[1..]))
+        .count();
+    let removed_defs = lines
+        .iter()
+        .filter(|l| l.starts_with('-') && is_definition(&l[1..]))
+        .count();
+
+    if added_defs > 0 && removed_defs > 0 {
+        ExcerptKind::Signature
+    } else if added_defs > 0 && !is_test_path(path) {
+        ExcerptKind::NewFunction
+    } else if is_test_path(path)
+        || lines
+            .iter()
+            .any(|l| l.starts_with('+') && has_test_marker(&l[1..]))
+    {
+        ExcerptKind::Test
+    } else {
+        ExcerptKind::Change
+    }
+}
+
+fn render_hunk(hunk: &Hunk, max_lines: usize) -> String {
+    let mut content = hunk.header.clone();
+    for line in hunk.lines.iter().take(max_lines) {
+        content.push('\n');
+        content.push_str(line);
+    }
+    if hunk.lines.len() > max_lines {
+        content.push_str("\n…");
+    }
+    content
+}
+
+fn render_text(selected: &[(usize, DiffExcerpt)]) -> String {
+    let mut text = String::new();
+    let mut last_commit: Option<usize> = None;
+    for (commit, excerpt) in selected {
+        if last_commit != Some(*commit) {
+            if last_commit.is_some() {
+                text.push('\n');
+            }
+            let short = excerpt.hash.get(..7).unwrap_or(&excerpt.hash);
+            text.push_str(&format!("[{}] {}\n", short, excerpt.message));
+            last_commit = Some(*commit);
+        }
+        text.push_str(&format!(
+            "{}（{}）\n```diff\n{}\n```\n",
+            excerpt.path,
+            excerpt.kind.label(),
+            excerpt.content
+        ));
+    }
+    text
+}
+
+/// 粗略估算 token 数：ASCII 约 4 个字符一个 token，中文等非 ASCII 字符按一个字一个 token
+fn estimate_tokens(text: &str) -> usize {
+    let ascii = text.chars().filter(|c| c.is_ascii()).count();
+    let others = text.chars().count() - ascii;
+    ascii.div_ceil(4) + others
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::TempRepo;
+
+    fn hunk(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn detects_test_calls() {
+        for line in [
+            "+describe('cart', () => {",
+            "+  it('adds items', () => {",
+            "+test(\"totals\", async () => {",
+            "+    #[test]",
+        ] {
+            assert_eq!(
+                classify_hunk("src/cart.ts", &hunk(&[line])),
+                ExcerptKind::Test,
+                "{}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn strips_prefix_from_quoted_and_spaced_paths() {
+        assert_eq!(strip_diff_prefix("b/src/main.rs", "b/"), "src/main.rs");
+        assert_eq!(
+            strip_diff_prefix("b/docs/read me.md\t", "b/"),
+            "docs/read me.md"
+        );
+        assert_eq!(strip_diff_prefix("a/文档/说明.md", "a/"), "文档/说明.md");
+        assert_eq!(
+            strip_diff_prefix("\"b/say \\\"hi\\\".txt\"", "b/"),
+            "say \"hi\".txt"
+        );
+        assert_eq!(
+            strip_diff_prefix("\"b/\\346\\226\\207.txt\"", "b/"),
+            "文.txt"
+        );
+    }
+
+    #[test]
+    fn collects_non_ascii_paths_from_patch_log() {
+        let repo = TempRepo::new("excerpt-paths");
+        repo.write("文档/说明.md", "标题\n");
+        repo.commit("docs: 添加说明");
+
+        let mut commits = Vec::new();
+        let mut hunks = Vec::new();
+        fetch_patch_log(
+            &repo.path(),
+            "2000-01-01",
+            "2099-12-31",
+            &[],
+            &mut commits,
+            &mut hunks,
+        )
+        .unwrap();
+
+        assert_eq!(commits.len(), 1);
+        assert_eq!(hunks[0].path, "文档/说明.md");
+    }
+
+    #[test]
+    fn ignores_calls_ending_with_test_names() {
+        for line in [
+            "+  form.submit(data);",
+            "+  await init(config);",
+            "+  const ok = regex.test(value);",
+            "+  audit(entry);",
+            "+  latest(items);",
+            "+  exit(1);",
+        ] {
+            assert_eq!(
+                classify_hunk("src/form.ts", &hunk(&[line])),
+                ExcerptKind::Change,
+                "{}",
+                line
+            );
+        }
+    }
+}
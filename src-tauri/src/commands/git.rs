@@ -1,8 +1,29 @@
+use super::asset::{flag_assets, summarize_assets, AssetKind, AssetSummary};
+use super::automation::{
+    automation_excluded, detect_automation, flag_automated, is_mass_rename, AutomationKind,
+};
+use super::branch::attach_branches;
+use super::component::{summarize_components, ComponentMatcher, ComponentSummary};
+use super::config::{find_project_by_path, load_config};
+use super::dedup::{attach_patch_ids, dedup_commits, pipe_through, CommitLocation};
+use super::hg::{ensure_hg_supported, is_hg_repository};
+use super::notes::attach_notes;
+use super::outlier::{flag_outliers, outlier_settings, OutlierKind};
+use super::pull_request::{fetch_pull_requests, PullRequest};
+use super::remote::attach_web_urls;
+use super::repo::{is_bundle_path, resolve_repo_path, revision_args};
+use super::revert::{pair_reverts, summarize_reverts, RevertSummary};
+use super::svn::is_svn_repository;
+use super::vcs::{backend_for, detect_vcs, VcsKind};
+use chrono::{DateTime, FixedOffset};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::{Mutex, RwLock};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
 pub struct GitCommit {
     pub hash: String,
     pub author: String,
@@ -10,12 +31,97 @@ pub struct GitCommit {
     pub date: String,
     pub message: String,
     pub files: Vec<GitFile>,
+    #[serde(default)]
+    pub components: Vec<String>,
+    #[serde(default)]
+    pub signature: Option<CommitSignature>,
+    #[serde(default)]
+    pub note: Option<String>, // refs/notes/dev-report 中的报告注释
+    #[serde(default)]
+    pub web_url: Option<String>,
+    #[serde(default)]
+    pub co_authors: Vec<String>, // Co-authored-by 尾注，格式同 "Name <email>"
+    #[serde(default)]
+    pub via_co_author: bool, // 选中的作者只以结对身份出现在该提交中
+    #[serde(default)]
+    pub patch_id: Option<String>,
+    #[serde(default)]
+    pub change_id: Option<String>, // Gerrit 的 Change-Id 尾注
+    #[serde(default)]
+    pub landed: Vec<CommitLocation>, // 同一改动的全部落点，只有一处时为空
+    #[serde(default)]
+    pub reverts: Option<String>, // 本提交回滚的提交
+    #[serde(default)]
+    pub reverted_by: Option<String>, // 时间范围内回滚了本提交的提交
+    #[serde(default)]
+    pub automation: Option<AutomationKind>, // 识别为自动化提交时的类别
+    #[serde(default)]
+    pub outlier: Option<OutlierKind>, // 格式化、引入第三方代码等批量改动
+    #[serde(default)]
+    pub branch: Option<String>, // 推断出的开发分支，主干上的提交为空
+    #[serde(default)]
+    pub repo: String, // 所属仓库路径，子模块中的提交为子模块路径
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// 提交签名（GPG / SSH / X.509）的校验结果，未签名的提交为 None
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommitSignature {
+    pub status: SignatureStatus,
+    pub signer: String,
+    pub key_id: String,
+}
+
+/// 对应 `%G?` 的取值
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    Good,             // G
+    Bad,              // B
+    Untrusted,        // U：签名有效但密钥未被信任
+    ExpiredSignature, // X
+    ExpiredKey,       // Y
+    RevokedKey,       // R
+    UnknownKey,       // E：缺少公钥等原因无法校验
+}
+
+impl SignatureStatus {
+    fn from_code(code: &str) -> Option<Self> {
+        match code.trim() {
+            "G" => Some(SignatureStatus::Good),
+            "B" => Some(SignatureStatus::Bad),
+            "U" => Some(SignatureStatus::Untrusted),
+            "X" => Some(SignatureStatus::ExpiredSignature),
+            "Y" => Some(SignatureStatus::ExpiredKey),
+            "R" => Some(SignatureStatus::RevokedKey),
+            "E" => Some(SignatureStatus::UnknownKey),
+            _ => None,
+        }
+    }
+
+    fn is_verified(self) -> bool {
+        matches!(self, SignatureStatus::Good | SignatureStatus::Untrusted)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct SignatureSummary {
+    pub signed: usize,
+    pub verified: usize, // 签名有效（G / U）
+    pub unsigned: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GitFile {
     pub status: String,
     pub path: String,
+    #[serde(default)]
+    pub components: Vec<String>,
+    #[serde(default)]
+    pub web_url: Option<String>,
+    #[serde(default)]
+    pub asset: Option<AssetKind>, // LFS 指针或二进制文件，不计入代码改动
+    #[serde(default)]
+    pub size: Option<u64>, // 资源文件变更后的字节数
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -32,6 +138,19 @@ pub struct GitStats {
     pub date_range: (String, String),
     pub sample_commits: Vec<GitCommit>, // 抽样展示，最多 50 条
     pub file_changes_summary: Vec<FileChangeSummary>,
+    pub component_summary: Vec<ComponentSummary>,
+    pub signature_summary: SignatureSummary,
+    pub revert_summary: RevertSummary,
+    #[serde(default)]
+    pub automated_count: usize, // 未计入 total_commits 的自动化提交数
+    #[serde(default)]
+    pub outlier_count: usize, // 抽样中的批量改动提交数
+    #[serde(default)]
+    pub pull_requests: Vec<PullRequest>, // 时间范围内合并的 PR，按合并时间倒序
+    #[serde(default)]
+    pub pull_requests_truncated: bool, // 合并提交超过上限，只解析了最近的部分
+    #[serde(default)]
+    pub asset_summary: AssetSummary, // 抽样中的资源文件更新，不计入文件变更统计
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -40,9 +159,175 @@ pub struct FileChangeSummary {
     pub change_count: usize,
 }
 
-const FIELD_SEPARATOR: &str = "\x1e";
+pub(crate) const FIELD_SEPARATOR: &str = "\x1e";
+
+/// git 调用失败的分类，命令边界处转换为面向用户的提示文本
+#[derive(Debug)]
+pub(crate) enum GitError {
+    /// 仓库属主与当前用户不一致，被 safe.directory 检查拒绝
+    DubiousOwnership {
+        path: String,
+    },
+    /// git 版本低于所用参数要求的最低版本
+    UnsupportedVersion {
+        found: String,
+        required: String,
+    },
+    Failed {
+        context: String,
+        stderr: String,
+    },
+}
+
+impl fmt::Display for GitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GitError::DubiousOwnership { path } => write!(
+                f,
+                "仓库所有者与当前用户不一致，Git 出于安全原因拒绝读取: {}。确认仓库可信后，可在项目设置中选择“信任此仓库”，或手动执行 git config --global --add safe.directory '{}'",
+                path, path
+            ),
+            GitError::UnsupportedVersion { found, required } => write!(
+                f,
+                "Git 版本过低: 当前 {}，至少需要 {}。请升级 Git，或在设置中指定其他 Git 可执行文件",
+                found, required
+            ),
+            GitError::Failed { context, stderr } => write!(f, "{}: {}", context, stderr),
+        }
+    }
+}
+
+impl From<GitError> for String {
+    fn from(err: GitError) -> Self {
+        err.to_string()
+    }
+}
+
+/// 根据 stderr 识别失败原因，所有 git 调用使用 LC_ALL=C，输出为固定的英文文案
+pub(crate) fn classify_failure(context: &str, path: &str, stderr: &[u8]) -> GitError {
+    let stderr = String::from_utf8_lossy(stderr).trim().to_string();
+    if stderr.contains("detected dubious ownership") {
+        return GitError::DubiousOwnership {
+            path: path.to_string(),
+        };
+    }
+    GitError::Failed {
+        context: context.to_string(),
+        stderr,
+    }
+}
+
+// 子进程只继承定位可执行文件、用户配置和 GnuPG 密钥环所需的变量，GIT_DIR、GIT_EXTERNAL_DIFF 等一律丢弃。
+// Gpg4win 的密钥环位于 %APPDATA%\gnupg，缺少这些变量时所有签名都会显示为无法校验
+pub(crate) const INHERITED_ENV: [&str; 14] = [
+    "PATH",
+    "HOME",
+    "USERPROFILE",
+    "HOMEDRIVE",
+    "HOMEPATH",
+    "SystemRoot",
+    "TEMP",
+    "TMP",
+    "TMPDIR",
+    "XDG_CONFIG_HOME",
+    "APPDATA",
+    "LOCALAPPDATA",
+    "GNUPGHOME",
+    "GPG_AGENT_INFO",
+];
+
+#[cfg(windows)]
+const NULL_HOOKS_PATH: &str = "NUL";
+#[cfg(not(windows))]
+const NULL_HOOKS_PATH: &str = "/dev/null";
+
+// 签名校验程序及其默认值，只采用用户全局配置中的值，仓库配置中的覆盖一律忽略
+const SIGNING_PROGRAMS: [(&str, &str); 3] = [
+    ("gpg.program", "gpg"),
+    ("gpg.ssh.program", "ssh-keygen"),
+    ("gpg.x509.program", "gpgsm"),
+];
+
+static SIGNING_OVERRIDES: Mutex<Option<Vec<String>>> = Mutex::new(None);
+
+/// 只清理环境变量、不附加 -c 覆盖的 git 命令，读取全局配置时使用
+fn isolated_git_command() -> Command {
+    let mut cmd = Command::new(git_executable());
+    cmd.env_clear();
+    for key in INHERITED_ENV {
+        if let Some(value) = std::env::var_os(key) {
+            cmd.env(key, value);
+        }
+    }
+    cmd.env("LC_ALL", "C")
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .env("GIT_OPTIONAL_LOCKS", "0");
+    cmd
+}
+
+/// 以 -c 传入的签名校验程序：用户在全局配置中指定了 gpg2 或不在 PATH 中的路径时沿用该值，
+/// 未配置时使用 PATH 中的默认程序。结果缓存到 git 可执行文件变化为止
+fn signing_overrides() -> Vec<String> {
+    if let Some(args) = SIGNING_OVERRIDES.lock().ok().and_then(|a| a.clone()) {
+        return args;
+    }
+    let output = isolated_git_command()
+        .args(["config", "--global", "-z", "--get-regexp", r"^gpg\."])
+        .output();
+    let raw = match output {
+        Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout).to_string(),
+        _ => String::new(),
+    };
+    // -z 下每条记录为 "key\nvalue\0"，key 已转为小写
+    let configured: Vec<(&str, &str)> = raw
+        .split('\0')
+        .filter_map(|entry| entry.split_once('\n'))
+        .collect();
+    let args: Vec<String> = SIGNING_PROGRAMS
+        .iter()
+        .flat_map(|(key, default)| {
+            let value = configured
+                .iter()
+                .rev()
+                .find(|(k, v)| k.eq_ignore_ascii_case(key) && !v.is_empty())
+                .map(|(_, v)| *v)
+                .unwrap_or(default);
+            ["-c".to_string(), format!("{}={}", key, value)]
+        })
+        .collect();
+    if let Ok(mut cached) = SIGNING_OVERRIDES.lock() {
+        *cached = Some(args.clone());
+    }
+    args
+}
+
+/// 构造加固过的 git 命令。被分析的仓库可能不可信，仓库配置中的
+/// core.fsmonitor、core.pager、diff 驱动、签名校验程序和 hooks 都可能执行任意程序，因此：
+/// 不读取系统级配置、固定 C 语言环境便于解析输出、禁用 fsmonitor/hooks/pager/外部 diff，
+/// 签名校验程序固定为用户全局配置的值，未配置时使用 PATH 中的 gpg / ssh-keygen / gpgsm。
+/// .gitattributes 指定的 diff 驱动名由仓库决定，textconv 无法在这里按名称统一关闭：
+/// blame、log -p、diff 等会执行 textconv 的命令必须显式传入 --no-textconv
+pub(crate) fn git_command() -> Command {
+    let mut cmd = isolated_git_command();
+    cmd.args([
+        "--no-pager",
+        "-c",
+        "core.fsmonitor=false",
+        "-c",
+        "core.untrackedCache=false",
+        "-c",
+        "core.pager=cat",
+        "-c",
+        "diff.external=",
+    ])
+    .args(signing_overrides())
+    .arg("-c")
+    .arg(format!("core.hooksPath={}", NULL_HOOKS_PATH));
+    cmd
+}
 
-fn validate_path(path: &str) -> Result<(), String> {
+pub(crate) fn validate_path(path: &str) -> Result<(), String> {
     let p = Path::new(path);
     if !p.is_absolute() {
         return Err("路径必须是绝对路径".to_string());
@@ -53,33 +338,279 @@ fn validate_path(path: &str) -> Result<(), String> {
     Ok(())
 }
 
-fn validate_date(date: &str) -> Result<(), String> {
+// 按所属项目的模块映射和 CODEOWNERS 标注提交涉及的业务模块。
+// project_path 为配置中的路径（可能是 bundle 文件），repo_path 为实际执行 git 命令的目录
+pub(crate) fn tag_components(project_path: &str, repo_path: &str, commits: &mut [GitCommit]) {
+    let (matcher, prefix) = component_matcher(project_path, repo_path);
+    matcher.tag_commits(commits, &prefix);
+}
+
+/// 项目对应的模块匹配器，以及子模块相对主仓库的路径前缀
+pub(crate) fn component_matcher(project_path: &str, repo_path: &str) -> (ComponentMatcher, String) {
+    let project = find_project_by_path(project_path);
+    let matcher = match &project {
+        Some(p) => ComponentMatcher::load(repo_path, &p.components, p.use_codeowners),
+        None => ComponentMatcher::load(repo_path, &[], true),
+    };
+    let prefix = project
+        .as_ref()
+        .and_then(|p| Path::new(project_path).strip_prefix(&p.repo_path).ok())
+        .map(|rel| rel.to_string_lossy().to_string())
+        .unwrap_or_default();
+    (matcher, prefix)
+}
+
+pub(crate) fn validate_date(date: &str) -> Result<(), String> {
     if date.len() > 30 || date.contains('\n') || date.starts_with('-') {
         return Err(format!("无效的日期格式: {}", date));
     }
     Ok(())
 }
 
-#[tauri::command]
-pub fn check_git_installed() -> Result<String, String> {
-    let output = Command::new("git")
+pub(crate) fn validate_authors(authors: &[String]) -> Result<(), String> {
+    for author in authors {
+        if author.starts_with('-') {
+            return Err(format!("无效的作者名: {}", author));
+        }
+    }
+    Ok(())
+}
+
+/// `--numstat` 的一条记录，二进制文件的行数为 None
+pub(crate) struct NumstatEntry {
+    pub added: Option<usize>,
+    pub deleted: Option<usize>,
+    pub path: String,
+}
+
+/// 解析 `--pretty=format:<FIELD_SEPARATOR>%H --numstat -z --no-renames` 的输出。
+/// 不加 -z 时非 ASCII 路径会被加引号并转义为八进制，与 ls-tree -z、cat-file 使用的原始路径对不上。
+/// 提交头可以在 %H 之后再以 FIELD_SEPARATOR 附加单行字段，由调用方自行拆分
+pub(crate) fn parse_numstat_z(raw: &[u8]) -> Vec<(String, Vec<NumstatEntry>)> {
+    let mut commits: Vec<(String, Vec<NumstatEntry>)> = Vec::new();
+    for token in raw.split(|&b| b == 0) {
+        let token = String::from_utf8_lossy(token);
+        // 提交头与该提交的第一条记录之间以换行分隔
+        let entry = match token.strip_prefix(FIELD_SEPARATOR) {
+            Some(rest) => {
+                let (hash, entry) = rest.split_once('\n').unwrap_or((rest, ""));
+                commits.push((hash.trim().to_string(), Vec::new()));
+                entry.to_string()
+            }
+            None => token.to_string(),
+        };
+        let fields: Vec<&str> = entry.splitn(3, '\t').collect();
+        if let (3, Some((_, entries))) = (fields.len(), commits.last_mut()) {
+            entries.push(NumstatEntry {
+                added: fields[0].parse().ok(),
+                deleted: fields[1].parse().ok(),
+                path: fields[2].to_string(),
+            });
+        }
+    }
+    commits
+}
+
+/// 解析 `--pretty=format:` 中每个字段都以 %x00 结尾、后接 `--numstat -z --no-renames` 的输出。
+/// 说明等多行字段中出现任何字符都不影响拆分；每条 numstat 以 NUL 结尾（首条前有换行），
+/// 提交之间再以一个空的 NUL 分隔
+pub(crate) fn parse_fields_numstat_z(
+    raw: &[u8],
+    field_count: usize,
+) -> Vec<(Vec<String>, Vec<NumstatEntry>)> {
+    let mut commits = Vec::new();
+    let mut tokens = raw
+        .split(|&b| b == 0)
+        .map(|token| String::from_utf8_lossy(token).to_string());
+    while let Some(first) = tokens.next() {
+        if first.trim().is_empty() {
+            continue;
+        }
+        let mut fields = vec![first.trim().to_string()];
+        fields.extend(tokens.by_ref().take(field_count - 1));
+        let mut entries = Vec::new();
+        for entry in tokens.by_ref() {
+            if entry.is_empty() {
+                break;
+            }
+            let parts: Vec<&str> = entry.trim_start_matches('\n').splitn(3, '\t').collect();
+            if parts.len() == 3 {
+                entries.push(NumstatEntry {
+                    added: parts[0].parse().ok(),
+                    deleted: parts[1].parse().ok(),
+                    path: parts[2].to_string(),
+                });
+            }
+        }
+        if fields.len() == field_count {
+            commits.push((fields, entries));
+        }
+    }
+    commits
+}
+
+/// 解析 `%ai` 格式的提交时间，如 `2024-01-15 10:00:00 +0800`
+pub(crate) fn parse_commit_date(date: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_str(date.trim(), "%Y-%m-%d %H:%M:%S %z").ok()
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitCapabilities {
+    pub executable: String,
+    pub version: String,
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    pub min_version: String,
+    pub supported: bool,
+    pub features: GitFeatures,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GitFeatures {
+    pub mailmap: bool,
+    pub name_status_z: bool,
+    pub end_of_options: bool,
+    pub worktree_porcelain: bool,
+    pub annotate_stdin: bool,
+}
+
+// --end-of-options 是所用参数中要求最高的（2.24）
+const MIN_GIT_VERSION: (u32, u32, u32) = (2, 24, 0);
+
+static GIT_EXECUTABLE: RwLock<Option<String>> = RwLock::new(None);
+static GIT_CAPABILITIES: Mutex<Option<GitCapabilities>> = Mutex::new(None);
+
+fn git_executable() -> String {
+    if let Some(executable) = GIT_EXECUTABLE.read().ok().and_then(|g| g.clone()) {
+        return executable;
+    }
+    let configured = load_config().map(|c| c.git_path).unwrap_or_default();
+    set_git_executable(&configured);
+    GIT_EXECUTABLE
+        .read()
+        .ok()
+        .and_then(|g| g.clone())
+        .unwrap_or_else(|| "git".to_string())
+}
+
+/// 配置加载或保存时调用，路径变化后重新检测版本
+pub(crate) fn set_git_executable(path: &str) {
+    let executable = match path.trim() {
+        "" => "git".to_string(),
+        p => p.to_string(),
+    };
+    if let Ok(mut current) = GIT_EXECUTABLE.write() {
+        if current.as_deref() == Some(executable.as_str()) {
+            return;
+        }
+        *current = Some(executable);
+    }
+    if let Ok(mut caps) = GIT_CAPABILITIES.lock() {
+        *caps = None;
+    }
+    if let Ok(mut overrides) = SIGNING_OVERRIDES.lock() {
+        *overrides = None;
+    }
+}
+
+// 兼容 "git version 2.39.2 (Apple Git-143)" 和 "git version 2.41.0.windows.1"
+fn parse_git_version(raw: &str) -> Option<(u32, u32, u32)> {
+    let version = raw.trim().strip_prefix("git version ")?;
+    let mut parts = version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|p| !p.is_empty())
+        .map(|p| p.parse::<u32>().ok());
+    let major = parts.next()??;
+    let minor = parts.next()??;
+    let patch = parts.next().flatten().unwrap_or(0);
+    Some((major, minor, patch))
+}
+
+fn detect_git_capabilities() -> Result<GitCapabilities, String> {
+    if let Some(caps) = GIT_CAPABILITIES.lock().ok().and_then(|c| c.clone()) {
+        return Ok(caps);
+    }
+
+    let executable = git_executable();
+    let output = git_command()
         .arg("--version")
         .output()
-        .map_err(|e| format!("Git 未安装或无法执行: {}", e))?;
+        .map_err(|e| format!("Git 未安装或无法执行 ({}): {}", executable, e))?;
+    if !output.status.success() {
+        return Err("Git 未安装，请先安装 Git".to_string());
+    }
 
-    if output.status.success() {
-        let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
-        Ok(version)
-    } else {
-        Err("Git 未安装，请先安装 Git".to_string())
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let (major, minor, patch) =
+        parse_git_version(&version).ok_or_else(|| format!("无法识别的 Git 版本: {}", version))?;
+    let at_least = |v: (u32, u32, u32)| (major, minor, patch) >= v;
+
+    let caps = GitCapabilities {
+        executable,
+        version,
+        major,
+        minor,
+        patch,
+        min_version: format!(
+            "{}.{}.{}",
+            MIN_GIT_VERSION.0, MIN_GIT_VERSION.1, MIN_GIT_VERSION.2
+        ),
+        supported: at_least(MIN_GIT_VERSION),
+        features: GitFeatures {
+            mailmap: at_least((1, 8, 2)),
+            name_status_z: at_least((1, 7, 0)),
+            end_of_options: at_least((2, 24, 0)),
+            worktree_porcelain: at_least((2, 7, 0)),
+            annotate_stdin: at_least((2, 35, 0)),
+        },
+    };
+
+    if let Ok(mut cached) = GIT_CAPABILITIES.lock() {
+        *cached = Some(caps.clone());
     }
+    Ok(caps)
+}
+
+/// 按版本选择参数写法时使用，检测失败时为空
+pub(crate) fn git_features() -> Option<GitFeatures> {
+    detect_git_capabilities().ok().map(|caps| caps.features)
+}
+
+/// 在执行任何依赖新参数的命令前检查版本，避免低版本 git 在解析阶段才报出含糊的错误
+pub(crate) fn ensure_git_supported() -> Result<(), String> {
+    let caps = detect_git_capabilities()?;
+    if !caps.supported {
+        return Err(GitError::UnsupportedVersion {
+            found: format!("{}.{}.{}", caps.major, caps.minor, caps.patch),
+            required: caps.min_version,
+        }
+        .into());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn check_git_installed() -> Result<String, String> {
+    ensure_git_supported()?;
+    detect_git_capabilities().map(|caps| caps.version)
+}
+
+#[tauri::command]
+pub fn get_git_capabilities() -> Result<GitCapabilities, String> {
+    detect_git_capabilities()
 }
 
 #[tauri::command]
 pub fn get_folder_name(path: String) -> Result<String, String> {
     let p = Path::new(&path);
-    let name = p
-        .file_name()
+    // bundle 文件去掉扩展名，与目录名的展示保持一致
+    let name = if is_bundle_path(&path) {
+        p.file_stem()
+    } else {
+        p.file_name()
+    };
+    let name = name
         .map(|n| n.to_string_lossy().to_string())
         .unwrap_or_else(|| path.clone());
     Ok(name)
@@ -87,29 +618,74 @@ pub fn get_folder_name(path: String) -> Result<String, String> {
 
 #[tauri::command]
 pub fn validate_repo_path(path: String) -> Result<bool, String> {
-    validate_path(&path)?;
+    if is_bundle_path(&path) {
+        let output = git_command()
+            .args(["bundle", "list-heads", "--end-of-options"])
+            .arg(&path)
+            .output()
+            .map_err(|e| format!("无法读取 bundle: {}", e))?;
+        return Ok(output.status.success());
+    }
+
+    match detect_vcs(&path) {
+        VcsKind::Git => validate_path(&path)?,
+        VcsKind::Mercurial => {
+            validate_path(&path)?;
+            ensure_hg_supported()?;
+            return Ok(is_hg_repository(&path));
+        }
+        VcsKind::Subversion => return Ok(is_svn_repository(&path)),
+    }
 
-    let output = Command::new("git")
-        .args(["rev-parse", "--is-inside-work-tree"])
+    // --git-dir 对工作区（含关联工作区）和裸仓库都能成功
+    let output = git_command()
+        .args(["rev-parse", "--git-dir"])
         .current_dir(&path)
         .output()
         .map_err(|e| format!("无法访问路径: {}", e))?;
 
-    Ok(output.status.success())
+    if !output.status.success() {
+        // 属主检查失败时仓库本身是有效的，返回可操作的错误而不是“不是 Git 仓库”
+        if let GitError::DubiousOwnership { path } = classify_failure("", &path, &output.stderr) {
+            return Err(GitError::DubiousOwnership { path }.into());
+        }
+        return Ok(false);
+    }
+    Ok(true)
 }
 
+/// 将仓库加入全局 safe.directory，用于处理属主检查失败的仓库
 #[tauri::command]
-pub fn get_git_authors(path: String) -> Result<Vec<String>, String> {
+pub fn trust_repository(path: String) -> Result<(), String> {
     validate_path(&path)?;
 
-    let output = Command::new("git")
+    let output = git_command()
+        .args(["config", "--global", "--add", "safe.directory"])
+        .arg(&path)
+        .output()
+        .map_err(|e| format!("写入 safe.directory 失败: {}", e))?;
+
+    if !output.status.success() {
+        return Err(classify_failure("写入 safe.directory 失败", &path, &output.stderr).into());
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_git_authors(path: String) -> Result<Vec<String>, String> {
+    let path = resolve_repo_path(&path)?;
+    backend_for(&path).authors()
+}
+
+pub(crate) fn list_git_authors(path: &str) -> Result<Vec<String>, String> {
+    let output = git_command()
         .args(["log", "--format=%an <%ae>", "--all"])
-        .current_dir(&path)
+        .current_dir(path)
         .output()
         .map_err(|e| format!("获取提交人失败: {}", e))?;
 
     if !output.status.success() {
-        return Err("获取提交人列表失败".to_string());
+        return Err(classify_failure("获取提交人列表失败", path, &output.stderr).into());
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
@@ -126,9 +702,13 @@ pub fn get_git_authors(path: String) -> Result<Vec<String>, String> {
 
 #[tauri::command]
 pub fn get_git_submodules(path: String) -> Result<Vec<GitSubmodule>, String> {
+    // bundle 只包含提交历史，没有可检出的子模块；其他版本控制系统不支持子模块
+    if is_bundle_path(&path) || detect_vcs(&path) != VcsKind::Git {
+        return Ok(vec![]);
+    }
     validate_path(&path)?;
 
-    let output = Command::new("git")
+    let output = git_command()
         .args(["submodule", "status"])
         .current_dir(&path)
         .output()
@@ -174,20 +754,17 @@ pub fn get_git_log(
     authors: Vec<String>,
     include_submodules: Vec<String>,
 ) -> Result<Vec<GitCommit>, String> {
-    validate_path(&path)?;
+    let repo_path = resolve_repo_path(&path)?;
     validate_date(&since)?;
     validate_date(&until)?;
+    validate_authors(&authors)?;
 
-    for author in &authors {
-        if author.starts_with('-') {
-            return Err(format!("无效的作者名: {}", author));
-        }
-    }
+    // 记录每个提交来自哪个仓库，用于合并 cherry-pick 和重复引入的改动
+    let mut all_commits: Vec<(String, GitCommit)> = Vec::new();
 
-    let mut all_commits = Vec::new();
-
-    let main_commits = fetch_git_log(&path, &since, &until, &authors)?;
-    all_commits.extend(main_commits);
+    let mut main_commits = fetch_git_log(&repo_path, &since, &until, &authors)?;
+    tag_components(&path, &repo_path, &mut main_commits);
+    all_commits.extend(main_commits.into_iter().map(|c| (path.clone(), c)));
 
     for sub_path in include_submodules {
         if let Err(e) = validate_path(&sub_path) {
@@ -195,58 +772,129 @@ pub fn get_git_log(
             continue;
         }
         match fetch_git_log(&sub_path, &since, &until, &authors) {
-            Ok(sub_commits) => all_commits.extend(sub_commits),
+            Ok(mut sub_commits) => {
+                tag_components(&sub_path, &sub_path, &mut sub_commits);
+                all_commits.extend(sub_commits.into_iter().map(|c| (sub_path.clone(), c)));
+            }
             Err(e) => eprintln!("子模块日志获取失败 ({}): {}", sub_path, e),
         }
     }
 
+    let mut all_commits = dedup_commits(all_commits);
     all_commits.sort_by(|a, b| b.date.cmp(&a.date));
     Ok(all_commits)
 }
 
+// 签名字段和结对署名放在标题之前，标题中即使出现分隔符也只影响最后一列
+fn log_format() -> String {
+    format!(
+        "--pretty=format:%H{sep}%an{sep}%ae{sep}%ai{sep}%G?{sep}%GS{sep}%GK{sep}%(trailers:key=Co-authored-by,valueonly,separator=%x1f){sep}%(trailers:key=Change-Id,valueonly,separator=%x1f){sep}%s",
+        sep = FIELD_SEPARATOR
+    )
+}
+
+const TRAILER_SEPARATOR: char = '\x1f';
+
 fn fetch_git_log(
     path: &str,
     since: &str,
     until: &str,
     authors: &[String],
 ) -> Result<Vec<GitCommit>, String> {
-    let format_str = format!(
-        "--pretty=format:%H{}%an{}%ae{}%ai{}%s",
-        FIELD_SEPARATOR, FIELD_SEPARATOR, FIELD_SEPARATOR, FIELD_SEPARATOR
-    );
+    eprintln!("获取 Git 日志: {} ({} -> {})", path, since, until);
+    // 限制最大提交数，防止大仓库卡死
+    backend_for(path).log(since, until, authors, 1000)
+}
+
+/// 项目是否把 Co-authored-by 尾注中的结对作者计入统计，默认开启
+pub(crate) fn co_authors_counted(path: &str) -> bool {
+    find_project_by_path(path)
+        .map(|p| p.count_co_authored)
+        .unwrap_or(true)
+}
+
+/// 按作者筛选时，项目开启了结对署名则同时收集
+/// Co-authored-by 尾注中出现选中作者的提交
+fn co_authors_enabled(path: &str, authors: &[String]) -> bool {
+    !authors.is_empty() && co_authors_counted(path)
+}
+
+pub(crate) fn author_filter(authors: &[String]) -> Vec<String> {
+    authors
+        .iter()
+        .map(|author| format!("--author={}", author))
+        .collect()
+}
+
+// 多个 --grep 之间是“或”的关系，与 --author 的匹配方式一致，作者名按正则处理
+fn co_author_filter(authors: &[String]) -> Vec<String> {
+    std::iter::once("--regexp-ignore-case".to_string())
+        .chain(
+            authors
+                .iter()
+                .map(|author| format!("--grep=^Co-authored-by:.*{}", author)),
+        )
+        .collect()
+}
 
+fn query_git_log(
+    path: &str,
+    since: &str,
+    until: &str,
+    filter: &[String],
+    limit: usize,
+) -> Result<Vec<GitCommit>, String> {
     let mut args = vec![
         "log".to_string(),
         format!("--since={}", since),
         format!("--until={}", until),
-        format_str,
+        log_format(),
         "--name-status".to_string(),
         "--no-merges".to_string(), // 排除合并提交，提升性能
-        "--max-count=1000".to_string(), // 限制最大提交数，防止大仓库卡死
+        format!("--max-count={}", limit),
     ];
+    args.extend_from_slice(filter);
+    args.extend(revision_args(path));
 
-    for author in authors {
-        args.push(format!("--author={}", author));
-    }
-
-    eprintln!("执行 Git 命令: git {} (path: {})", args.join(" "), path);
-
-    let output = Command::new("git")
+    // 非 ASCII 路径原样输出，与 numstat -z、cat-file 使用的路径一致，也便于在报告中展示
+    let output = git_command()
+        .args(["-c", "core.quotePath=false"])
         .args(&args)
         .current_dir(path)
         .output()
         .map_err(|e| format!("获取 Git 日志失败: {}", e))?;
 
     if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Git log 执行失败: {}", stderr));
+        return Err(classify_failure("Git log 执行失败", path, &output.stderr).into());
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
-    eprintln!("Git 日志输出大小: {} bytes", stdout.len());
     parse_git_log(&stdout)
 }
 
+/// 按哈希读取提交及其文件列表，不做自动化识别、补丁去重等附加处理
+pub(crate) fn fetch_commits_by_hash(path: &str, hashes: &[&str]) -> Result<Vec<GitCommit>, String> {
+    if hashes.is_empty() {
+        return Ok(Vec::new());
+    }
+    let format = log_format();
+    let input: String = hashes.iter().map(|h| format!("{}\n", h)).collect();
+    let output = pipe_through(
+        path,
+        &[
+            "-c",
+            "core.quotePath=false",
+            "log",
+            "--no-walk=unsorted",
+            "--stdin",
+            format.as_str(),
+            "--name-status",
+        ],
+        input.into_bytes(),
+    )?;
+    parse_git_log(&String::from_utf8_lossy(&output))
+}
+
 fn parse_git_log(raw: &str) -> Result<Vec<GitCommit>, String> {
     let mut commits = Vec::new();
     let mut current_commit: Option<GitCommit> = None;
@@ -262,15 +910,43 @@ fn parse_git_log(raw: &str) -> Result<Vec<GitCommit>, String> {
                 commits.push(commit);
             }
 
-            let parts: Vec<&str> = line.splitn(5, FIELD_SEPARATOR).collect();
-            if parts.len() == 5 {
+            let parts: Vec<&str> = line.splitn(10, FIELD_SEPARATOR).collect();
+            if parts.len() == 10 {
+                let signature =
+                    SignatureStatus::from_code(parts[4]).map(|status| CommitSignature {
+                        status,
+                        signer: parts[5].to_string(),
+                        key_id: parts[6].to_string(),
+                    });
                 current_commit = Some(GitCommit {
                     hash: parts[0].to_string(),
                     author: parts[1].to_string(),
                     email: parts[2].to_string(),
                     date: parts[3].to_string(),
-                    message: parts[4].to_string(),
+                    message: parts[9].to_string(),
                     files: Vec::new(),
+                    components: Vec::new(),
+                    signature,
+                    note: None,
+                    web_url: None,
+                    co_authors: parts[7]
+                        .split(TRAILER_SEPARATOR)
+                        .map(|c| c.trim().to_string())
+                        .filter(|c| !c.is_empty())
+                        .collect(),
+                    via_co_author: false,
+                    patch_id: None,
+                    change_id: parts[8]
+                        .split(TRAILER_SEPARATOR)
+                        .map(|id| id.trim().to_string())
+                        .find(|id| !id.is_empty()),
+                    landed: Vec::new(),
+                    reverts: None,
+                    reverted_by: None,
+                    automation: None,
+                    outlier: None,
+                    branch: None,
+                    repo: String::new(),
                 });
             }
         } else if let Some(ref mut commit) = current_commit {
@@ -279,6 +955,10 @@ fn parse_git_log(raw: &str) -> Result<Vec<GitCommit>, String> {
                 commit.files.push(GitFile {
                     status: parts[0].chars().next().unwrap_or('M').to_string(),
                     path: parts[1].to_string(),
+                    components: Vec::new(),
+                    web_url: None,
+                    asset: None,
+                    size: None,
                 });
             }
         }
@@ -299,45 +979,69 @@ pub fn get_git_stats(
     authors: Vec<String>,
     include_submodules: Vec<String>,
 ) -> Result<GitStats, String> {
-    validate_path(&path)?;
+    let repo_path = resolve_repo_path(&path)?;
     validate_date(&since)?;
     validate_date(&until)?;
-
-    for author in &authors {
-        if author.starts_with('-') {
-            return Err(format!("无效的作者名: {}", author));
-        }
-    }
+    validate_authors(&authors)?;
 
     eprintln!("获取 Git 统计信息: {} ({} -> {})", path, since, until);
 
-    // 1. 获取统计数据（不含文件列表，速度快）
-    let mut stats_args = vec![
-        "log".to_string(),
-        format!("--since={}", since),
-        format!("--until={}", until),
-        "--format=%H".to_string(),
-        "--no-merges".to_string(),
-    ];
-    for author in &authors {
-        stats_args.push(format!("--author={}", author));
+    // Mercurial 和 SVN 仓库没有签名、回滚、PR 等 git 专有的统计项
+    if detect_vcs(&repo_path) != VcsKind::Git {
+        let backend = backend_for(&repo_path);
+        let mut automated_count = 0;
+        let total_commits = backend.count_commits(&since, &until, &authors, &mut automated_count)?;
+        let mut sample_commits = backend.log(&since, &until, &authors, 50)?;
+        if automation_excluded(&path) {
+            sample_commits.retain(|c| c.automation.is_none());
+        }
+        tag_components(&path, &repo_path, &mut sample_commits);
+        return Ok(GitStats {
+            automated_count,
+            ..summarize_samples(total_commits, sample_commits, &since, &until)
+        });
     }
 
-    let output = Command::new("git")
-        .args(&stats_args)
-        .current_dir(&path)
-        .output()
-        .map_err(|e| format!("获取 Git 统计失败: {}", e))?;
-
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let main_commit_count = stdout.lines().filter(|l| !l.trim().is_empty()).count();
+    // 1. 获取统计数据（不含文件列表，速度快），每行一个提交的哈希和签名状态
+    let mut signature_summary = SignatureSummary::default();
+    let mut revert_summary = RevertSummary::default();
+    let mut automated_count = 0;
+    let main_hashes = count_commits(
+        &repo_path,
+        &since,
+        &until,
+        &authors,
+        &mut signature_summary,
+        &mut automated_count,
+    )?;
+    if let Err(e) = summarize_reverts(
+        &repo_path,
+        &since,
+        &until,
+        &main_hashes,
+        &mut revert_summary,
+    ) {
+        eprintln!("统计回滚提交失败 ({}): {}", path, e);
+    }
+    let (mut pull_requests, mut pull_requests_truncated) =
+        fetch_pull_requests(&repo_path, &since, &until, &authors).unwrap_or_else(|e| {
+            eprintln!("解析 PR 失败 ({}): {}", path, e);
+            (Vec::new(), false)
+        });
 
     // 2. 获取抽样数据（最多 50 条，带文件信息）
-    let sample_commits = fetch_git_log_limited(&path, &since, &until, &authors, 50)?;
+    let mut sample_commits = fetch_git_log_limited(&repo_path, &since, &until, &authors, 50)?;
+    if automation_excluded(&path) {
+        sample_commits.retain(|c| c.automation.is_none());
+    }
+    tag_components(&path, &repo_path, &mut sample_commits);
 
     // 3. 处理子模块
-    let mut total_commits = main_commit_count;
-    let mut all_sample_commits = sample_commits;
+    let mut total_commits = main_hashes.len();
+    let mut all_sample_commits: Vec<(String, GitCommit)> = sample_commits
+        .into_iter()
+        .map(|c| (path.clone(), c))
+        .collect();
     
     for sub_path in include_submodules {
         if let Err(e) = validate_path(&sub_path) {
@@ -345,100 +1049,339 @@ pub fn get_git_stats(
             continue;
         }
         
-        let sub_output = Command::new("git")
-            .args(&stats_args)
-            .current_dir(&sub_path)
-            .output();
-        
-        if let Ok(out) = sub_output {
-            let sub_stdout = String::from_utf8_lossy(&out.stdout);
-            total_commits += sub_stdout.lines().filter(|l| !l.trim().is_empty()).count();
+        if let Ok(hashes) = count_commits(
+            &sub_path,
+            &since,
+            &until,
+            &authors,
+            &mut signature_summary,
+            &mut automated_count,
+        ) {
+            total_commits += hashes.len();
+            if let Err(e) =
+                summarize_reverts(&sub_path, &since, &until, &hashes, &mut revert_summary)
+            {
+                eprintln!("统计回滚提交失败 ({}): {}", sub_path, e);
+            }
+        }
+        match fetch_pull_requests(&sub_path, &since, &until, &authors) {
+            Ok((sub_prs, truncated)) => {
+                pull_requests.extend(sub_prs);
+                pull_requests_truncated |= truncated;
+            }
+            Err(e) => eprintln!("解析 PR 失败 ({}): {}", sub_path, e),
         }
         
-        if let Ok(sub_samples) = fetch_git_log_limited(&sub_path, &since, &until, &authors, 20) {
-            all_sample_commits.extend(sub_samples);
+        if let Ok(mut sub_samples) = fetch_git_log_limited(&sub_path, &since, &until, &authors, 20) {
+            if automation_excluded(&sub_path) {
+                sub_samples.retain(|c| c.automation.is_none());
+            }
+            tag_components(&sub_path, &sub_path, &mut sub_samples);
+            all_sample_commits.extend(sub_samples.into_iter().map(|c| (sub_path.clone(), c)));
         }
     }
 
     // 限制抽样总数
+    let mut all_sample_commits = dedup_commits(all_sample_commits);
     all_sample_commits.truncate(50);
     all_sample_commits.sort_by(|a, b| b.date.cmp(&a.date));
 
-    // 4. 统计文件变更
-    let mut file_changes: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    pull_requests.sort_by(|a, b| b.merged_at.cmp(&a.merged_at));
+    Ok(GitStats {
+        signature_summary,
+        revert_summary,
+        automated_count,
+        pull_requests,
+        pull_requests_truncated,
+        ..summarize_samples(total_commits, all_sample_commits, &since, &until)
+    })
+}
+
+/// 由提交总数和抽样提交汇总出 GitStats，签名、回滚、PR 等 git 专有的统计项由调用方补充
+pub(crate) fn summarize_samples(
+    total_commits: usize,
+    sample_commits: Vec<GitCommit>,
+    since: &str,
+    until: &str,
+) -> GitStats {
+    let mut file_changes: std::collections::HashMap<String, usize> =
+        std::collections::HashMap::new();
     let mut authors_set: std::collections::HashSet<String> = std::collections::HashSet::new();
     let mut dates: Vec<String> = Vec::new();
+    // 批量改动的文件可能有上千个，折叠后不计入文件变更统计
+    let collapse_outliers = outlier_settings().collapse_in_stats;
+    let counts_files = |c: &GitCommit| !(collapse_outliers && c.outlier.is_some());
 
-    for commit in &all_sample_commits {
+    for commit in &sample_commits {
         authors_set.insert(commit.author.clone());
         dates.push(commit.date.clone());
-        for file in &commit.files {
+        if !counts_files(commit) {
+            continue;
+        }
+        for file in commit.files.iter().filter(|f| f.asset.is_none()) {
             *file_changes.entry(file.path.clone()).or_insert(0) += 1;
         }
     }
 
     let mut file_changes_summary: Vec<FileChangeSummary> = file_changes
         .into_iter()
-        .map(|(path, count)| FileChangeSummary { path, change_count: count })
+        .map(|(path, count)| FileChangeSummary {
+            path,
+            change_count: count,
+        })
         .collect();
     file_changes_summary.sort_by(|a, b| b.change_count.cmp(&a.change_count));
     file_changes_summary.truncate(20); // 只返回前 20 个最常修改的文件
 
     dates.sort();
     let date_range = (
-        dates.first().cloned().unwrap_or(since.clone()),
-        dates.last().cloned().unwrap_or(until.clone()),
+        dates.first().cloned().unwrap_or(since.to_string()),
+        dates.last().cloned().unwrap_or(until.to_string()),
     );
 
-    let total_files_changed = all_sample_commits.iter().map(|c| c.files.len()).sum();
-
-    Ok(GitStats {
+    let total_files_changed = sample_commits
+        .iter()
+        .filter(|c| counts_files(c))
+        .map(|c| c.files.iter().filter(|f| f.asset.is_none()).count())
+        .sum();
+    let outlier_count = sample_commits
+        .iter()
+        .filter(|c| c.outlier.is_some())
+        .count();
+    let component_summary = summarize_components(&sample_commits);
+    let asset_summary = summarize_assets(&sample_commits);
+
+    GitStats {
         total_commits,
         total_files_changed,
         authors: authors_set.into_iter().collect(),
         date_range,
-        sample_commits: all_sample_commits,
+        sample_commits,
         file_changes_summary,
-    })
+        component_summary,
+        signature_summary: SignatureSummary::default(),
+        revert_summary: RevertSummary::default(),
+        automated_count: 0,
+        outlier_count,
+        pull_requests: Vec::new(),
+        pull_requests_truncated: false,
+        asset_summary,
+    }
 }
 
-fn fetch_git_log_limited(
+/// 统计口径内的提交哈希，供其他聚合与提交数保持同一组提交
+pub(crate) fn counted_commits(
     path: &str,
     since: &str,
     until: &str,
     authors: &[String],
-    limit: usize,
-) -> Result<Vec<GitCommit>, String> {
-    let format_str = format!(
-        "--pretty=format:%H{}%an{}%ae{}%ai{}%s",
-        FIELD_SEPARATOR, FIELD_SEPARATOR, FIELD_SEPARATOR, FIELD_SEPARATOR
-    );
+) -> Result<HashSet<String>, String> {
+    let mut signatures = SignatureSummary::default();
+    let mut automated = 0;
+    count_commits(path, since, until, authors, &mut signatures, &mut automated)
+}
 
-    let mut args = vec![
-        "log".to_string(),
-        format!("--since={}", since),
-        format!("--until={}", until),
-        format_str,
-        "--name-status".to_string(),
-        "--no-merges".to_string(),
-        format!("--max-count={}", limit),
-    ];
+/// 统计口径使用的作者筛选条件：作者本人，以及开启结对署名时的 Co-authored-by 匹配。
+/// 各条件分别执行一次 git log，结果按哈希去重
+pub(crate) fn author_filters(path: &str, authors: &[String]) -> Vec<Vec<String>> {
+    let mut filters = vec![author_filter(authors)];
+    if co_authors_enabled(path, authors) {
+        filters.push(co_author_filter(authors));
+    }
+    filters
+}
 
-    for author in authors {
-        args.push(format!("--author={}", author));
+// 收集统计范围内的提交哈希并累计签名状态，作者和结对署名两次查询的结果按哈希去重。
+// 项目未包含自动化提交时，按作者、说明和文件状态（批量重命名）识别出的提交计入 automated 而不计入结果
+pub(crate) fn count_commits(
+    path: &str,
+    since: &str,
+    until: &str,
+    authors: &[String],
+    summary: &mut SignatureSummary,
+    automated: &mut usize,
+) -> Result<HashSet<String>, String> {
+    let filters = author_filters(path, authors);
+    let exclude_automated = automation_excluded(path);
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut skipped: HashSet<String> = HashSet::new();
+    for filter in filters {
+        let mut args = vec![
+            "log".to_string(),
+            format!("--since={}", since),
+            format!("--until={}", until),
+            format!(
+                "--format=%H{sep}%G?{sep}%an{sep}%ae{sep}%s",
+                sep = FIELD_SEPARATOR
+            ),
+            "--no-merges".to_string(),
+        ];
+        // 识别批量重命名需要文件状态，不排除自动化提交时省去 diff 的开销
+        if exclude_automated {
+            args.push("--name-status".to_string());
+        }
+        let output = git_command()
+            .args(&args)
+            .args(&filter)
+            .args(revision_args(path))
+            .current_dir(path)
+            .output()
+            .map_err(|e| format!("获取 Git 统计失败: {}", e))?;
+
+        if !output.status.success() {
+            return Err(classify_failure("获取 Git 统计失败", path, &output.stderr).into());
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut records: Vec<(Vec<&str>, Vec<&str>)> = Vec::new();
+        for line in stdout.lines() {
+            if line.contains(FIELD_SEPARATOR) {
+                records.push((line.splitn(5, FIELD_SEPARATOR).collect(), Vec::new()));
+            } else if let (Some((_, statuses)), Some((status, _))) =
+                (records.last_mut(), line.split_once('\t'))
+            {
+                statuses.push(status);
+            }
+        }
+
+        for (parts, statuses) in records {
+            if parts.len() != 5 || seen.contains(parts[0]) || skipped.contains(parts[0]) {
+                continue;
+            }
+            if exclude_automated
+                && (detect_automation(parts[2], parts[3], parts[4]).is_some()
+                    || is_mass_rename(&statuses))
+            {
+                skipped.insert(parts[0].to_string());
+                continue;
+            }
+            seen.insert(parts[0].to_string());
+            match SignatureStatus::from_code(parts[1]) {
+                Some(status) => {
+                    summary.signed += 1;
+                    if status.is_verified() {
+                        summary.verified += 1;
+                    }
+                }
+                None => summary.unsigned += 1,
+            }
+        }
     }
+    *automated += skipped.len();
+    Ok(seen)
+}
 
-    let output = Command::new("git")
-        .args(&args)
-        .current_dir(path)
-        .output()
-        .map_err(|e| format!("获取 Git 日志失败: {}", e))?;
+pub(crate) fn fetch_git_log_limited(
+    path: &str,
+    since: &str,
+    until: &str,
+    authors: &[String],
+    limit: usize,
+) -> Result<Vec<GitCommit>, String> {
+    let mut commits = query_git_log(path, since, until, &author_filter(authors), limit)?;
+
+    if co_authors_enabled(path, authors) {
+        let paired = query_git_log(path, since, until, &co_author_filter(authors), limit)?;
+        let seen: HashSet<String> = commits.iter().map(|c| c.hash.clone()).collect();
+        commits.extend(
+            paired
+                .into_iter()
+                .filter(|c| !seen.contains(&c.hash))
+                .map(|mut c| {
+                    c.via_co_author = true;
+                    c
+                }),
+        );
+        commits.sort_by(|a, b| b.date.cmp(&a.date));
+        commits.truncate(limit);
+    }
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Git log 执行失败: {}", stderr));
+    flag_automated(&mut commits);
+    flag_outliers(path, &mut commits);
+    flag_assets(path, &mut commits);
+    attach_notes(path, &mut commits);
+    attach_web_urls(path, &mut commits);
+    let patch_ids = attach_patch_ids(path, &mut commits);
+    pair_reverts(path, since, until, &patch_ids, &mut commits);
+    attach_branches(path, &mut commits);
+    Ok(commits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::TempRepo;
+
+    #[test]
+    fn parses_nul_terminated_fields_and_numstat() {
+        let raw = "aaa\0empty\n\0\0bbb\0fix: 订单 #12\n\nbody \x1e with\ttab\n\0\n3\t1\t订单/服务.rs\0-\t-\t图.png\0";
+        let commits = parse_fields_numstat_z(raw.as_bytes(), 2);
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].0, ["aaa", "empty\n"]);
+        assert!(commits[0].1.is_empty());
+        assert_eq!(
+            commits[1].0,
+            ["bbb", "fix: 订单 #12\n\nbody \x1e with\ttab\n"]
+        );
+        let entries: Vec<(&str, Option<usize>, Option<usize>)> = commits[1]
+            .1
+            .iter()
+            .map(|e| (e.path.as_str(), e.added, e.deleted))
+            .collect();
+        assert_eq!(
+            entries,
+            [("订单/服务.rs", Some(3), Some(1)), ("图.png", None, None)]
+        );
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    parse_git_log(&stdout)
+    #[test]
+    fn verifies_signatures_with_custom_gnupghome() {
+        if Command::new("gpg").arg("--version").output().is_err() {
+            return;
+        }
+        let home = std::env::temp_dir().join(format!("dev-report-gnupg-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&home);
+        std::fs::create_dir_all(&home).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&home, std::fs::Permissions::from_mode(0o700)).unwrap();
+        }
+        std::env::set_var("GNUPGHOME", &home);
+
+        let status = Command::new("gpg")
+            .args(["--batch", "--passphrase", "", "--quick-gen-key"])
+            .args(["Signer <signer@example.com>", "ed25519", "sign", "never"])
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success());
+
+        let repo = TempRepo::new("gnupghome");
+        repo.write("a.txt", "a\n");
+        repo.git(&["add", "-A"]);
+        repo.git(&[
+            "-c",
+            "user.signingkey=signer@example.com",
+            "commit",
+            "-q",
+            "-S",
+            "-m",
+            "signed",
+        ]);
+        let output = git_command()
+            .args(["log", "-1", "--format=%G?"])
+            .current_dir(repo.path())
+            .output()
+            .unwrap();
+
+        let _ = Command::new("gpgconf")
+            .args(["--kill", "gpg-agent"])
+            .output();
+        std::env::remove_var("GNUPGHOME");
+        let _ = std::fs::remove_dir_all(&home);
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "G");
+    }
 }
@@ -0,0 +1,350 @@
+use super::automation::{automation_excluded, detect_automation, flag_automated};
+use super::git::{GitCommit, GitFile, FIELD_SEPARATOR, INHERITED_ENV};
+use super::vcs::VcsBackend;
+use std::process::Command;
+use std::sync::Mutex;
+
+// HGRCSKIPREPO 从 5.4 开始支持
+const MIN_HG_VERSION: (u32, u32) = (5, 4);
+
+static HG_SUPPORTED: Mutex<Option<Result<(), String>>> = Mutex::new(None);
+
+/// 通过 hg 命令行读取 Mercurial 仓库，输出格式与 git 实现保持一致
+pub(crate) struct HgBackend {
+    path: String,
+}
+
+impl HgBackend {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+        }
+    }
+
+    fn run(&self, context: &str, args: &[String]) -> Result<String, String> {
+        ensure_hg_supported().map_err(|e| format!("{}: {}", context, e))?;
+        let output = hg_command()
+            .args(args)
+            .current_dir(&self.path)
+            .output()
+            .map_err(|e| format!("{}: 无法执行 hg（{}）", context, e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("{}: {}", context, stderr.trim()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+/// 构造加固过的 hg 命令。被分析的仓库可能不可信，.hg/hgrc 中的 hooks 和 extensions
+/// 都可以执行任意代码，因此 HGRCSKIPREPO 跳过仓库配置，HGRCPATH 置空同时跳过系统和用户配置，
+/// 与 git_command 一样只按内置默认值运行；HGPLAIN 屏蔽影响输出格式的选项（别名、颜色、分页、本地化等）。
+/// 低版本会忽略 HGRCSKIPREPO，调用前需先通过 ensure_hg_supported 检查
+pub(crate) fn hg_command() -> Command {
+    let mut cmd = Command::new("hg");
+    cmd.env_clear();
+    for key in INHERITED_ENV {
+        if let Some(value) = std::env::var_os(key) {
+            cmd.env(key, value);
+        }
+    }
+    cmd.env("HGRCSKIPREPO", "1")
+        .env("HGRCPATH", "")
+        .env("HGPLAIN", "1")
+        .env("HGENCODING", "utf-8")
+        .args(["--noninteractive", "--config", "ui.report_untrusted=false"]);
+    cmd
+}
+
+/// 检查结果在进程内缓存，hg 未安装时同样返回错误
+pub(crate) fn ensure_hg_supported() -> Result<(), String> {
+    let mut cached = HG_SUPPORTED.lock().map_err(|e| e.to_string())?;
+    cached
+        .get_or_insert_with(|| {
+            let output = hg_command()
+                .args(["version", "--quiet"])
+                .output()
+                .map_err(|e| format!("Mercurial 未安装或无法执行: {}", e))?;
+            let raw = String::from_utf8_lossy(&output.stdout);
+            let version = parse_hg_version(&raw)
+                .ok_or_else(|| format!("无法识别的 Mercurial 版本: {}", raw.trim()))?;
+            if version < MIN_HG_VERSION {
+                return Err(format!(
+                    "Mercurial 版本过低（{}.{}），需要 {}.{} 及以上才能安全读取仓库",
+                    version.0, version.1, MIN_HG_VERSION.0, MIN_HG_VERSION.1
+                ));
+            }
+            Ok(())
+        })
+        .clone()
+}
+
+// 兼容 "Mercurial Distributed SCM (version 6.1.1)" 和 "(version 5.9.3+hg42)"
+fn parse_hg_version(raw: &str) -> Option<(u32, u32)> {
+    let (_, version) = raw.split_once("(version ")?;
+    let mut parts = version
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|p| p.parse::<u32>().ok());
+    Some((parts.next()??, parts.next()??))
+}
+
+pub(crate) fn is_hg_repository(path: &str) -> bool {
+    if ensure_hg_supported().is_err() {
+        return false;
+    }
+    hg_command()
+        .arg("root")
+        .current_dir(path)
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+// revset 中的字符串字面量
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// 与 git log --since/--until/--author/--no-merges 等价的 revset，按提交时间倒序
+fn revset(since: &str, until: &str, authors: &[String]) -> String {
+    let mut expr = format!(
+        "not merge() and date({})",
+        quote(&format!("{} to {}", since, until))
+    );
+    if !authors.is_empty() {
+        let users: Vec<String> = authors
+            .iter()
+            .map(|a| format!("user({})", quote(a)))
+            .collect();
+        expr.push_str(&format!(" and ({})", users.join(" or ")));
+    }
+    format!("sort({}, -date)", expr)
+}
+
+impl VcsBackend for HgBackend {
+    fn log(
+        &self,
+        since: &str,
+        until: &str,
+        authors: &[String],
+        limit: usize,
+    ) -> Result<Vec<GitCommit>, String> {
+        // 首行为提交信息，随后每行一个文件，状态字母与 git --name-status 一致；
+        // C 行为复制来源，供 parse_hg_log 合并重命名
+        let template = format!(
+            "{{node}}{sep}{{author|person}}{sep}{{author|email}}{sep}{{date|isodatesec}}{sep}{{desc|firstline}}\\n\
+             {{file_adds % 'A\\t{{file}}\\n'}}{{file_mods % 'M\\t{{file}}\\n'}}{{file_dels % 'D\\t{{file}}\\n'}}\
+             {{file_copies % 'C\\t{{source}}\\t{{name}}\\n'}}",
+            sep = FIELD_SEPARATOR
+        );
+        let stdout = self.run(
+            "Mercurial 日志获取失败",
+            &[
+                "log".to_string(),
+                "--rev".to_string(),
+                revset(since, until, authors),
+                "--limit".to_string(),
+                limit.to_string(),
+                "--template".to_string(),
+                template,
+            ],
+        )?;
+
+        let mut commits = parse_hg_log(&stdout);
+        flag_automated(&mut commits);
+        Ok(commits)
+    }
+
+    fn count_commits(
+        &self,
+        since: &str,
+        until: &str,
+        authors: &[String],
+        automated: &mut usize,
+    ) -> Result<usize, String> {
+        let stdout = self.run(
+            "Mercurial 统计失败",
+            &[
+                "log".to_string(),
+                "--rev".to_string(),
+                revset(since, until, authors),
+                "--template".to_string(),
+                format!(
+                    "{{author|person}}{sep}{{author|email}}{sep}{{desc|firstline}}\\n",
+                    sep = FIELD_SEPARATOR
+                ),
+            ],
+        )?;
+
+        let exclude_automated = automation_excluded(&self.path);
+        let mut total = 0;
+        for line in stdout.lines() {
+            let parts: Vec<&str> = line.splitn(3, FIELD_SEPARATOR).collect();
+            if parts.len() != 3 {
+                continue;
+            }
+            if exclude_automated && detect_automation(parts[0], parts[1], parts[2]).is_some() {
+                *automated += 1;
+            } else {
+                total += 1;
+            }
+        }
+        Ok(total)
+    }
+
+    fn authors(&self) -> Result<Vec<String>, String> {
+        let stdout = self.run(
+            "获取提交人列表失败",
+            &[
+                "log".to_string(),
+                "--template".to_string(),
+                "{author|person} <{author|email}>\\n".to_string(),
+            ],
+        )?;
+        let mut authors: Vec<String> = stdout
+            .lines()
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty())
+            .collect();
+        authors.sort();
+        authors.dedup();
+        Ok(authors)
+    }
+}
+
+/// hg 把重命名记录为新文件的新增加旧文件的删除，另有一条复制来源；
+/// 来源在同一提交中被删除时合并为一条 R，与 git 的重命名检测一致，未删除的视为普通新增
+fn parse_hg_log(raw: &str) -> Vec<GitCommit> {
+    let mut commits: Vec<GitCommit> = Vec::new();
+    let mut copies: Vec<Vec<(String, String)>> = Vec::new();
+    for line in raw.lines() {
+        if line.contains(FIELD_SEPARATOR) {
+            let parts: Vec<&str> = line.splitn(5, FIELD_SEPARATOR).collect();
+            if parts.len() == 5 {
+                commits.push(GitCommit {
+                    hash: parts[0].to_string(),
+                    author: parts[1].to_string(),
+                    email: parts[2].to_string(),
+                    date: parts[3].to_string(),
+                    message: parts[4].to_string(),
+                    ..Default::default()
+                });
+                copies.push(Vec::new());
+            }
+        } else if let (Some(commit), Some((status, path))) =
+            (commits.last_mut(), line.split_once('\t'))
+        {
+            if status == "C" {
+                if let (Some(copied), Some((source, name))) =
+                    (copies.last_mut(), path.split_once('\t'))
+                {
+                    copied.push((source.to_string(), name.to_string()));
+                }
+                continue;
+            }
+            commit.files.push(GitFile {
+                status: status.to_string(),
+                path: path.to_string(),
+                components: Vec::new(),
+                web_url: None,
+                asset: None,
+                size: None,
+            });
+        }
+    }
+
+    for (commit, copied) in commits.iter_mut().zip(copies) {
+        for (source, name) in copied {
+            let Some(deleted) = commit
+                .files
+                .iter()
+                .position(|f| f.status == "D" && f.path == source)
+            else {
+                continue;
+            };
+            if let Some(added) = commit
+                .files
+                .iter_mut()
+                .find(|f| f.status == "A" && f.path == name)
+            {
+                added.status = "R".to_string();
+                commit.files.remove(deleted);
+            }
+        }
+    }
+    commits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hg_versions() {
+        assert_eq!(
+            parse_hg_version("Mercurial Distributed SCM (version 6.1.1)\n"),
+            Some((6, 1))
+        );
+        assert_eq!(
+            parse_hg_version("Mercurial Distributed SCM (version 5.9.3+hg42)"),
+            Some((5, 9))
+        );
+        assert_eq!(
+            parse_hg_version("Mercurial Distributed SCM (version 5)"),
+            None
+        );
+        assert_eq!(parse_hg_version("hg: unknown command"), None);
+    }
+
+    #[test]
+    fn parses_template_output_and_folds_renames() {
+        let raw = [
+            "abc123\x1eAlice\x1ealice@example.com\x1e2024-01-15 10:00:00 +0800\x1erename: move util",
+            "A\tsrc/util/mod.rs",
+            "A\tsrc/copy.rs",
+            "M\tsrc/main.rs",
+            "D\tsrc/util.rs",
+            "C\tsrc/util.rs\tsrc/util/mod.rs",
+            "C\tsrc/main.rs\tsrc/copy.rs",
+            "def456\x1eBob\x1ebob@example.com\x1e2024-01-14 09:00:00 +0800\x1einit",
+            "A\tREADME.md",
+            "",
+        ]
+        .join("\n");
+
+        let commits = parse_hg_log(&raw);
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].hash, "abc123");
+        assert_eq!(commits[0].author, "Alice");
+        assert_eq!(commits[0].email, "alice@example.com");
+        assert_eq!(commits[0].date, "2024-01-15 10:00:00 +0800");
+        assert_eq!(commits[0].message, "rename: move util");
+        let files: Vec<(&str, &str)> = commits[0]
+            .files
+            .iter()
+            .map(|f| (f.status.as_str(), f.path.as_str()))
+            .collect();
+        assert_eq!(
+            files,
+            [
+                ("R", "src/util/mod.rs"),
+                ("A", "src/copy.rs"),
+                ("M", "src/main.rs")
+            ]
+        );
+        assert_eq!(commits[1].files.len(), 1);
+    }
+
+    #[test]
+    fn quotes_authors_in_revsets() {
+        assert_eq!(quote(r#"a"b\c"#), r#""a\"b\\c""#);
+        assert_eq!(
+            revset("2024-01-01", "2024-01-31", &[r#"Bob "B" \ Co"#.to_string()]),
+            r#"sort(not merge() and date("2024-01-01 to 2024-01-31") and (user("Bob \"B\" \\ Co")), -date)"#
+        );
+        assert_eq!(
+            revset("2024-01-01", "2024-01-31", &[]),
+            r#"sort(not merge() and date("2024-01-01 to 2024-01-31"), -date)"#
+        );
+    }
+}
@@ -1,3 +1,28 @@
 pub mod git;
 pub mod config;
 pub mod llm;
+pub mod component;
+pub mod churn;
+pub mod activity;
+pub mod sessions;
+pub mod timesheet;
+pub mod repo;
+pub mod notes;
+pub mod excerpt;
+pub mod remote;
+pub mod dedup;
+pub mod revert;
+pub mod survival;
+pub mod ticket;
+pub mod compare;
+pub mod team;
+pub mod automation;
+pub mod outlier;
+pub mod branch;
+pub mod pull_request;
+pub mod vcs;
+pub mod hg;
+pub mod svn;
+pub mod asset;
+#[cfg(test)]
+mod test_util;
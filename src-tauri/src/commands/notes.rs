@@ -0,0 +1,155 @@
+use super::dedup::pipe_through;
+use super::git::{classify_failure, git_command, GitCommit};
+use super::repo::{is_bundle_path, resolve_repo_path};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::process::Stdio;
+
+/// 报告注释使用独立的 notes 引用，不影响用户自己的 refs/notes/commits
+pub(crate) const NOTES_REF: &str = "refs/notes/dev-report";
+
+/// 将报告注释附加到提交上，读取失败时保持原样
+pub(crate) fn attach_notes(repo_path: &str, commits: &mut [GitCommit]) {
+    if commits.is_empty() {
+        return;
+    }
+    let hashes: HashSet<&str> = commits.iter().map(|c| c.hash.as_str()).collect();
+    let notes = match read_notes(repo_path, &hashes) {
+        Ok(notes) => notes,
+        Err(e) => {
+            eprintln!("读取提交注释失败 ({}): {}", repo_path, e);
+            return;
+        }
+    };
+    for commit in commits.iter_mut() {
+        commit.note = notes.get(&commit.hash).cloned();
+    }
+}
+
+// notes list 给出 (注释 blob, 提交) 对，再用一次 cat-file --batch 批量读取内容
+fn read_notes(repo_path: &str, hashes: &HashSet<&str>) -> Result<HashMap<String, String>, String> {
+    let output = git_command()
+        .args(["notes", "--ref", NOTES_REF, "list"])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("读取提交注释失败: {}", e))?;
+    if !output.status.success() {
+        return Err(classify_failure("读取提交注释失败", repo_path, &output.stderr).into());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let pairs: Vec<(String, String)> = stdout
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .filter(|(_, commit)| hashes.contains(commit))
+        .map(|(blob, commit)| (blob.to_string(), commit.to_string()))
+        .collect();
+    if pairs.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let input: String = pairs
+        .iter()
+        .map(|(blob, _)| format!("{}\n", blob))
+        .collect();
+    let output = pipe_through(repo_path, &["cat-file", "--batch"], input.into_bytes())?;
+
+    let contents = parse_batch_output(&output);
+    Ok(pairs
+        .into_iter()
+        .filter_map(|(blob, commit)| contents.get(&blob).map(|note| (commit, note.clone())))
+        .collect())
+}
+
+// 格式：`<sha> blob <size>\n<content>\n`，按 size 截取内容，注释中可以包含任意换行
+fn parse_batch_output(raw: &[u8]) -> HashMap<String, String> {
+    let mut contents = HashMap::new();
+    let mut rest = raw;
+
+    while let Some(header_end) = rest.iter().position(|&b| b == b'\n') {
+        let header = String::from_utf8_lossy(&rest[..header_end]).to_string();
+        rest = &rest[header_end + 1..];
+
+        let fields: Vec<&str> = header.split(' ').collect();
+        if fields.len() != 3 {
+            continue; // `<sha> missing`
+        }
+        let size: usize = match fields[2].parse() {
+            Ok(size) if size <= rest.len() => size,
+            _ => break,
+        };
+        let note = String::from_utf8_lossy(&rest[..size]).trim().to_string();
+        contents.insert(fields[0].to_string(), note);
+        rest = rest.get(size + 1..).unwrap_or_default();
+    }
+
+    contents
+}
+
+fn resolve_commit(repo_path: &str, hash: &str) -> Result<String, String> {
+    if hash.is_empty() || hash.len() > 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("无效的提交哈希: {}", hash));
+    }
+    let output = git_command()
+        .args(["rev-parse", "--verify", "--quiet", "--end-of-options"])
+        .arg(format!("{}^{{commit}}", hash))
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("查找提交失败: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("提交不存在: {}", hash));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[tauri::command]
+pub fn get_commit_note(path: String, hash: String) -> Result<Option<String>, String> {
+    let repo_path = resolve_repo_path(&path)?;
+    let full_hash = resolve_commit(&repo_path, &hash)?;
+
+    let hashes = HashSet::from([full_hash.as_str()]);
+    let mut notes = read_notes(&repo_path, &hashes)?;
+    Ok(notes.remove(&full_hash))
+}
+
+/// 写入或覆盖报告注释，内容为空时删除
+#[tauri::command]
+pub fn set_commit_note(path: String, hash: String, note: String) -> Result<(), String> {
+    // bundle 镜像每次同步都会按 bundle 内容覆盖引用，写入的注释无法保留
+    if is_bundle_path(&path) {
+        return Err("bundle 是只读来源，无法添加注释".to_string());
+    }
+    let repo_path = resolve_repo_path(&path)?;
+    let full_hash = resolve_commit(&repo_path, &hash)?;
+
+    let note = note.trim();
+    let mut cmd = git_command();
+    cmd.args(["notes", "--ref", NOTES_REF]);
+    if note.is_empty() {
+        cmd.args(["remove", "--ignore-missing"]);
+    } else {
+        // 通过 stdin 传入内容，避免注释以 `-` 开头时被当作参数
+        cmd.args(["add", "--force", "--file=-"]);
+    }
+    let mut child = cmd
+        .arg(&full_hash)
+        .current_dir(&repo_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("写入提交注释失败: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(note.as_bytes())
+            .map_err(|e| format!("写入提交注释失败: {}", e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("写入提交注释失败: {}", e))?;
+    if !output.status.success() {
+        return Err(classify_failure("写入提交注释失败", &path, &output.stderr).into());
+    }
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use super::config::{load_config, GlobalConfig, OutlierConfig};
+use super::dedup::pipe_through;
+use super::git::{GitCommit, FIELD_SEPARATOR};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 明显偏离日常开发的提交，统计中单独处理以免主导汇总结果
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutlierKind {
+    WhitespaceOnly, // 只有空白改动，通常是格式化
+    ManyFiles,      // 涉及文件数超过阈值，如引入第三方代码
+    LargeChurn,     // 改动行数超过阈值
+}
+
+pub(crate) fn outlier_settings() -> OutlierConfig {
+    load_config()
+        .map(|c| c.outliers)
+        .unwrap_or_else(|_| GlobalConfig::default().outliers)
+}
+
+/// 标注提交列表中的批量改动，读取失败时保持原样
+pub(crate) fn flag_outliers(repo_path: &str, commits: &mut [GitCommit]) {
+    if commits.is_empty() {
+        return;
+    }
+    let settings = outlier_settings();
+    let (churn, visible) = match (
+        numstat_by_commit(repo_path, commits, false),
+        numstat_by_commit(repo_path, commits, true),
+    ) {
+        (Ok(churn), Ok(visible)) => (churn, visible),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("统计提交改动行数失败 ({}): {}", repo_path, e);
+            return;
+        }
+    };
+
+    for commit in commits.iter_mut() {
+        let (entries, lines) = churn.get(&commit.hash).copied().unwrap_or_default();
+        // 忽略空白后没有任何文件变化；二进制文件在两种统计中都会出现，不会被误判
+        let whitespace_only = entries > 0 && visible.get(&commit.hash).is_none_or(|v| v.0 == 0);
+        commit.outlier = if whitespace_only {
+            Some(OutlierKind::WhitespaceOnly)
+        } else if commit.files.len() >= settings.max_files {
+            Some(OutlierKind::ManyFiles)
+        } else if lines >= settings.max_churn {
+            Some(OutlierKind::LargeChurn)
+        } else {
+            None
+        };
+    }
+}
+
+/// 每个提交的 (numstat 条目数, 增删行数之和)，ignore_whitespace 时忽略空白和空行改动
+fn numstat_by_commit(
+    repo_path: &str,
+    commits: &[GitCommit],
+    ignore_whitespace: bool,
+) -> Result<HashMap<String, (usize, usize)>, String> {
+    let format = format!("--format={}%H", FIELD_SEPARATOR);
+    let mut args = vec![
+        "log",
+        "--no-walk=unsorted",
+        "--stdin",
+        "--numstat",
+        format.as_str(),
+    ];
+    if ignore_whitespace {
+        args.extend(["-w", "--ignore-blank-lines"]);
+    }
+    let input: String = commits.iter().map(|c| format!("{}\n", c.hash)).collect();
+    let output = pipe_through(repo_path, &args, input.into_bytes())?;
+
+    let mut stats: HashMap<String, (usize, usize)> = HashMap::new();
+    let mut current: Option<String> = None;
+    for line in String::from_utf8_lossy(&output).lines() {
+        if let Some(hash) = line.strip_prefix(FIELD_SEPARATOR) {
+            let hash = hash.trim().to_string();
+            stats.entry(hash.clone()).or_default();
+            current = Some(hash);
+        } else if let Some(hash) = &current {
+            let fields: Vec<&str> = line.splitn(3, '\t').collect();
+            if fields.len() == 3 {
+                let entry = stats.entry(hash.clone()).or_default();
+                entry.0 += 1;
+                // 二进制文件的行数为 "-"，按 0 处理
+                entry.1 += fields[0].parse::<usize>().unwrap_or(0)
+                    + fields[1].parse::<usize>().unwrap_or(0);
+            }
+        }
+    }
+    Ok(stats)
+}
@@ -0,0 +1,461 @@
+use super::git::{author_filters, classify_failure, git_command, parse_fields_numstat_z};
+use super::remote::HostKind;
+use super::repo::revision_args;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// 从合并提交说明中解析出的 PR / MR，不需要访问平台 API
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PullRequest {
+    pub number: u64,
+    pub title: String,
+    pub source_branch: Option<String>,
+    pub target_branch: Option<String>,
+    pub platform: HostKind,
+    pub merge_hash: String,
+    pub merged_at: String,
+    pub merged_by: String,
+    pub commits: Vec<String>, // 该 PR 带入的提交，按作者筛选时只含选中作者的提交
+}
+
+pub(crate) struct ParsedMerge {
+    pub platform: HostKind,
+    pub number: u64,
+    pub title: String,
+    pub source_branch: Option<String>,
+    pub target_branch: Option<String>,
+}
+
+// 单次统计解析的合并提交上限，超出时只保留最近的合并并在 GitStats 中标记
+pub(crate) const MAX_MERGES: usize = 200;
+
+/// 时间范围内合并的 PR，以及合并提交是否超过 MAX_MERGES 被截断。
+/// 按作者筛选时只保留带入了选中作者提交（含结对署名）的 PR
+pub(crate) fn fetch_pull_requests(
+    path: &str,
+    since: &str,
+    until: &str,
+    authors: &[String],
+) -> Result<(Vec<PullRequest>, bool), String> {
+    let output = git_command()
+        .args([
+            "log".to_string(),
+            "--merges".to_string(),
+            format!("--since={}", since),
+            format!("--until={}", until),
+            "--pretty=format:%H%x00%P%x00%ai%x00%an%x00%B%x00".to_string(),
+            "-z".to_string(),
+            format!("--max-count={}", MAX_MERGES + 1),
+        ])
+        .args(revision_args(path))
+        .current_dir(path)
+        .output()
+        .map_err(|e| format!("获取合并提交失败: {}", e))?;
+    if !output.status.success() {
+        return Err(classify_failure("获取合并提交失败", path, &output.stderr).into());
+    }
+
+    let mut records: Vec<Vec<String>> = parse_fields_numstat_z(&output.stdout, 5)
+        .into_iter()
+        .map(|(fields, _)| fields)
+        .collect();
+    let truncated = records.len() > MAX_MERGES;
+    records.truncate(MAX_MERGES);
+
+    let mut merges = Vec::new();
+    for record in records {
+        let parsed = match parse_merge_message(&record[4]) {
+            Some(parsed) => parsed,
+            None => continue, // 普通的分支合并
+        };
+        let parents: Vec<&str> = record[1].split(' ').collect();
+        if parents.len() < 2 {
+            continue;
+        }
+        let (base, tip) = (parents[0].to_string(), parents[1].to_string());
+        merges.push((record, parsed, base, tip));
+    }
+
+    let ranges: Vec<(&str, &str)> = merges
+        .iter()
+        .map(|(_, _, base, tip)| (base.as_str(), tip.as_str()))
+        .collect();
+    let members = merged_commits(path, &ranges, authors)?;
+
+    let mut pull_requests = Vec::new();
+    for ((record, parsed, _, _), commits) in merges.into_iter().zip(members) {
+        if !authors.is_empty() && commits.is_empty() {
+            continue;
+        }
+        pull_requests.push(PullRequest {
+            number: parsed.number,
+            title: parsed.title,
+            source_branch: parsed.source_branch,
+            target_branch: parsed.target_branch,
+            platform: parsed.platform,
+            merge_hash: record[0].clone(),
+            merged_at: record[2].clone(),
+            merged_by: record[3].clone(),
+            commits,
+        });
+    }
+    Ok((pull_requests, truncated))
+}
+
+/// 每个合并提交的第二个父提交带入、第一个父提交中没有的非合并提交，按 rev-list 的时间倒序。
+/// 一次 rev-list 读出所有父提交共同祖先之后的提交图再在内存中求差集：共同祖先及更早的提交
+/// 都是每个第一个父提交的祖先，本来就不会出现在差集中
+fn merged_commits(
+    path: &str,
+    ranges: &[(&str, &str)],
+    authors: &[String],
+) -> Result<Vec<Vec<String>>, String> {
+    if ranges.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut tips: Vec<&str> = ranges
+        .iter()
+        .flat_map(|(base, tip)| [*base, *tip])
+        .collect();
+    tips.sort_unstable();
+    tips.dedup();
+    let boundary = common_ancestor(path, &tips)
+        .map(|hash| vec!["--not".to_string(), hash])
+        .unwrap_or_default();
+
+    let mut order: Vec<String> = Vec::new();
+    let mut parents: HashMap<String, Vec<String>> = HashMap::new();
+    let graph = rev_list(path, &["--parents"], &tips, &boundary)?;
+    for line in graph.lines() {
+        let mut hashes = line.split(' ').map(str::to_string);
+        if let Some(hash) = hashes.next() {
+            order.push(hash.clone());
+            parents.insert(hash, hashes.collect());
+        }
+    }
+
+    // 按作者筛选时先取出提交图中匹配的提交，作者和结对署名两个条件取并集
+    let selected: Option<HashSet<String>> = if authors.is_empty() {
+        None
+    } else {
+        let mut selected = HashSet::new();
+        for filter in author_filters(path, authors) {
+            let mut options = vec!["--no-merges".to_string()];
+            options.extend(filter);
+            let options: Vec<&str> = options.iter().map(String::as_str).collect();
+            let matched = rev_list(path, &options, &tips, &boundary)?;
+            selected.extend(matched.lines().map(str::to_string));
+        }
+        Some(selected)
+    };
+
+    Ok(ranges
+        .iter()
+        .map(|(base, tip)| {
+            let excluded = ancestors(&parents, base);
+            let included = ancestors(&parents, tip);
+            order
+                .iter()
+                .filter(|hash| included.contains(*hash) && !excluded.contains(*hash))
+                .filter(|hash| parents.get(*hash).is_some_and(|p| p.len() <= 1))
+                .filter(|hash| selected.as_ref().is_none_or(|s| s.contains(*hash)))
+                .cloned()
+                .collect()
+        })
+        .collect())
+}
+
+fn rev_list(
+    path: &str,
+    options: &[&str],
+    tips: &[&str],
+    boundary: &[String],
+) -> Result<String, String> {
+    let output = git_command()
+        .arg("rev-list")
+        .args(options)
+        .args(tips)
+        .args(boundary)
+        .current_dir(path)
+        .output()
+        .map_err(|e| format!("获取 PR 提交失败: {}", e))?;
+    if !output.status.success() {
+        return Err(classify_failure("获取 PR 提交失败", path, &output.stderr).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+// 所有父提交的共同祖先，历史不相交时为空
+fn common_ancestor(path: &str, tips: &[&str]) -> Option<String> {
+    let output = git_command()
+        .args(["merge-base", "--octopus"])
+        .args(tips)
+        .current_dir(path)
+        .output()
+        .ok()?;
+    let hash = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !hash.is_empty()).then_some(hash)
+}
+
+// 提交图内 start 及其全部祖先，图外的提交不再展开
+fn ancestors(parents: &HashMap<String, Vec<String>>, start: &str) -> HashSet<String> {
+    let mut seen = HashSet::new();
+    let mut stack = vec![start.to_string()];
+    while let Some(hash) = stack.pop() {
+        let Some(next) = parents.get(&hash) else {
+            continue;
+        };
+        if seen.insert(hash) {
+            stack.extend(next.iter().cloned());
+        }
+    }
+    seen
+}
+
+/// 识别各平台默认的合并提交说明：
+/// - GitHub：`Merge pull request #12 from owner/branch`，正文首行为标题
+/// - GitLab：`Merge branch 'a' into 'b'`，正文为标题及 `See merge request group/project!12`
+/// - Gitea：`Merge pull request 'title' (#12) from branch into main`
+/// - Bitbucket Cloud：`Merged in branch (pull request #12)`，正文首行为标题
+/// - Bitbucket Server：`Pull request #12: title`，正文含 `Merge in PROJ/repo from a to b`
+pub(crate) fn parse_merge_message(message: &str) -> Option<ParsedMerge> {
+    let mut lines = message.trim().lines();
+    let subject = lines.next()?.trim();
+    let body: Vec<&str> = lines.map(str::trim).filter(|l| !l.is_empty()).collect();
+    let first_body_line = body.first().map(|l| l.to_string());
+
+    if let Some(rest) = subject.strip_prefix("Merge pull request #") {
+        let (number, source) = rest.split_once(" from ")?;
+        let source = source.split_whitespace().next()?;
+        // 跨仓库 PR 的来源为 owner/branch，分支名本身也可能包含斜杠
+        let branch = source.split_once('/').map_or(source, |(_, b)| b);
+        return Some(ParsedMerge {
+            platform: HostKind::Github,
+            number: number.trim().parse().ok()?,
+            title: first_body_line.unwrap_or_default(),
+            source_branch: Some(branch.to_string()),
+            target_branch: None,
+        });
+    }
+
+    if let Some(rest) = subject.strip_prefix("Merge pull request '") {
+        let (title, rest) = rest.rsplit_once("' (#")?;
+        let (number, rest) = rest.split_once(')')?;
+        let (source, target) = match rest.trim().strip_prefix("from ") {
+            Some(branches) => match branches.rsplit_once(" into ") {
+                Some((source, target)) => (Some(source), Some(target)),
+                None => (Some(branches), None),
+            },
+            None => (None, None),
+        };
+        return Some(ParsedMerge {
+            platform: HostKind::Gitea,
+            number: number.parse().ok()?,
+            title: title.to_string(),
+            source_branch: source.map(str::to_string),
+            target_branch: target.map(str::to_string),
+        });
+    }
+
+    if let Some(rest) = subject.strip_prefix("Merged in ") {
+        let (source, rest) = rest.split_once(" (pull request #")?;
+        let (number, _) = rest.split_once(')')?;
+        return Some(ParsedMerge {
+            platform: HostKind::Bitbucket,
+            number: number.parse().ok()?,
+            title: first_body_line.unwrap_or_default(),
+            source_branch: Some(source.trim().to_string()),
+            target_branch: None,
+        });
+    }
+
+    if let Some(rest) = subject.strip_prefix("Pull request #") {
+        let (number, title) = rest.split_once(':')?;
+        let (source, target) = body
+            .iter()
+            .find_map(|l| l.strip_prefix("Merge in "))
+            .and_then(|l| l.split_once(" from "))
+            .and_then(|(_, branches)| branches.rsplit_once(" to "))
+            .map_or((None, None), |(s, t)| {
+                (Some(s.to_string()), Some(t.to_string()))
+            });
+        return Some(ParsedMerge {
+            platform: HostKind::Bitbucket,
+            number: number.trim().parse().ok()?,
+            title: title.trim().to_string(),
+            source_branch: source,
+            target_branch: target,
+        });
+    }
+
+    // GitLab 只能通过正文中的 "See merge request" 与普通分支合并区分
+    let number = body
+        .iter()
+        .find_map(|l| l.strip_prefix("See merge request "))
+        .and_then(|r| r.rsplit_once('!'))
+        .and_then(|(_, n)| n.trim().parse().ok())?;
+    let (source, target) = subject
+        .strip_prefix("Merge branch '")
+        .and_then(|r| r.split_once("' into '"))
+        .map_or((None, None), |(s, t)| {
+            (
+                Some(s.to_string()),
+                Some(t.trim_end_matches('\'').to_string()),
+            )
+        });
+    Some(ParsedMerge {
+        platform: HostKind::Gitlab,
+        number,
+        title: first_body_line
+            .filter(|l| !l.starts_with("See merge request "))
+            .unwrap_or_default(),
+        source_branch: source,
+        target_branch: target,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::TempRepo;
+
+    #[test]
+    fn parses_platform_merge_messages() {
+        // (说明, 平台, 编号, 标题, 来源分支, 目标分支)
+        let cases = [
+            (
+                "Merge pull request #12 from alice/feature/login\n\nAdd login page",
+                HostKind::Github,
+                12,
+                "Add login page",
+                Some("feature/login"),
+                None,
+            ),
+            (
+                "Merge pull request #7 from fix-typo\n\nFix typo",
+                HostKind::Github,
+                7,
+                "Fix typo",
+                Some("fix-typo"),
+                None,
+            ),
+            (
+                "Merge branch 'feature/pay' into 'main'\n\n接入支付\n\nSee merge request group/sub/app!34",
+                HostKind::Gitlab,
+                34,
+                "接入支付",
+                Some("feature/pay"),
+                Some("main"),
+            ),
+            (
+                "Merge pull request 'Fix ' (#3) parsing' (#45) from topic into main",
+                HostKind::Gitea,
+                45,
+                "Fix ' (#3) parsing",
+                Some("topic"),
+                Some("main"),
+            ),
+            (
+                "Merged in feature/report (pull request #8)\n\nWeekly report export",
+                HostKind::Bitbucket,
+                8,
+                "Weekly report export",
+                Some("feature/report"),
+                None,
+            ),
+            (
+                "Pull request #21: Add export\n\nMerge in PROJ/app from feature/export to master",
+                HostKind::Bitbucket,
+                21,
+                "Add export",
+                Some("feature/export"),
+                Some("master"),
+            ),
+        ];
+        for (message, platform, number, title, source, target) in cases {
+            let parsed = parse_merge_message(message).unwrap();
+            assert_eq!(parsed.platform, platform, "{}", message);
+            assert_eq!(parsed.number, number, "{}", message);
+            assert_eq!(parsed.title, title, "{}", message);
+            assert_eq!(parsed.source_branch.as_deref(), source, "{}", message);
+            assert_eq!(parsed.target_branch.as_deref(), target, "{}", message);
+        }
+    }
+
+    #[test]
+    fn ignores_plain_branch_merges() {
+        assert!(parse_merge_message("Merge branch 'develop' into 'main'").is_none());
+        assert!(parse_merge_message("Merge branch 'develop'\n\nsync").is_none());
+        assert!(parse_merge_message("Merge remote-tracking branch 'origin/main'").is_none());
+    }
+
+    #[test]
+    fn collects_commits_of_each_merge_in_one_walk() {
+        let repo = TempRepo::new("pull-request-members");
+        repo.write("a.txt", "a\n");
+        repo.commit("init");
+        let mainline = String::from_utf8(repo.output(&["branch", "--show-current"])).unwrap();
+        let mainline = mainline.trim();
+
+        repo.git(&["checkout", "-q", "-b", "one"]);
+        repo.write("b.txt", "b\n");
+        repo.commit("first change");
+        repo.write("c.txt", "c\n");
+        repo.commit_as(
+            "paired change\n\nCo-authored-by: pair <pair@example.com>",
+            "other <other@example.com>",
+        );
+        repo.git(&["checkout", "-q", mainline]);
+        repo.git(&[
+            "merge",
+            "-q",
+            "--no-ff",
+            "one",
+            "-m",
+            "Merge pull request #1 from team/one",
+        ]);
+
+        repo.git(&["checkout", "-q", "-b", "two"]);
+        repo.write("d.txt", "d\n");
+        repo.commit_as("second change", "other <other@example.com>");
+        repo.git(&["checkout", "-q", mainline]);
+        repo.git(&[
+            "merge",
+            "-q",
+            "--no-ff",
+            "two",
+            "-m",
+            "Merge pull request #2 from team/two",
+        ]);
+
+        let subjects = |hashes: &[String]| -> Vec<String> {
+            hashes
+                .iter()
+                .map(|h| {
+                    let subject = repo.output(&["log", "-1", "--format=%s", h]);
+                    String::from_utf8(subject).unwrap().trim().to_string()
+                })
+                .collect()
+        };
+
+        let (all, truncated) =
+            fetch_pull_requests(&repo.path(), "2000-01-01", "2099-12-31", &[]).unwrap();
+        assert!(!truncated);
+        let numbers: Vec<u64> = all.iter().map(|pr| pr.number).collect();
+        assert_eq!(numbers, [2, 1]);
+        assert_eq!(subjects(&all[0].commits), ["second change"]);
+        assert_eq!(subjects(&all[1].commits), ["paired change", "first change"]);
+
+        // 只以结对身份出现的作者也能找到对应的 PR
+        let (paired, _) = fetch_pull_requests(
+            &repo.path(),
+            "2000-01-01",
+            "2099-12-31",
+            &["pair".to_string()],
+        )
+        .unwrap();
+        assert_eq!(paired.len(), 1);
+        assert_eq!(paired[0].number, 1);
+        assert_eq!(subjects(&paired[0].commits), ["paired change"]);
+    }
+}
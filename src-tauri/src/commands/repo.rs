@@ -0,0 +1,272 @@
+use super::config::get_config_dir;
+use super::git::{classify_failure, ensure_git_supported, git_command, validate_path};
+use super::svn::validate_svn_target;
+use super::vcs::{detect_vcs, VcsKind};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RepoKind {
+    WorkTree,
+    Bare,
+    Bundle,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RepoIdentity {
+    pub kind: RepoKind,
+    pub common_dir: String,
+    pub worktrees: Vec<String>,
+}
+
+// 同步标记文件，其修改时间早于 bundle 时重新导入
+const BUNDLE_SYNC_MARKER: &str = "dev-report-bundle-synced";
+
+pub(crate) fn is_bundle_path(path: &str) -> bool {
+    let p = Path::new(path);
+    p.is_file() && p.extension().map(|e| e == "bundle").unwrap_or(false)
+}
+
+/// 将项目路径解析为可以直接执行 git 命令的目录：工作区和裸仓库原样返回，
+/// bundle 文件导入到配置目录下的只读镜像后返回镜像路径
+pub(crate) fn resolve_repo_path(path: &str) -> Result<String, String> {
+    // Mercurial 和 SVN 仓库不经过 git，也就不要求安装 git
+    match detect_vcs(path) {
+        VcsKind::Git => {}
+        VcsKind::Mercurial => {
+            validate_path(path)?;
+            return Ok(path.to_string());
+        }
+        VcsKind::Subversion => {
+            validate_svn_target(path)?;
+            return Ok(path.to_string());
+        }
+    }
+    if !Path::new(path).is_absolute() {
+        return Err("路径必须是绝对路径".to_string());
+    }
+    ensure_git_supported()?;
+    if is_bundle_path(path) {
+        return sync_bundle_mirror(path).map(|p| p.to_string_lossy().to_string());
+    }
+    validate_path(path)?;
+    Ok(path.to_string())
+}
+
+/// 日志类命令需要追加的修订范围：bundle 镜像取全部引用，
+/// 有多个关联工作区时合并各工作区的 HEAD，其余情况沿用默认的 HEAD
+pub(crate) fn revision_args(repo_path: &str) -> Vec<String> {
+    if is_bundle_mirror(repo_path) {
+        return vec!["--all".to_string()];
+    }
+
+    let heads = worktree_heads(repo_path);
+    if heads.len() > 1 {
+        std::iter::once("--end-of-options".to_string())
+            .chain(heads)
+            .collect()
+    } else {
+        Vec::new()
+    }
+}
+
+fn is_bundle_mirror(repo_path: &str) -> bool {
+    Path::new(repo_path).join(BUNDLE_SYNC_MARKER).exists()
+}
+
+/// 镜像目录名取规范化路径的 sha256，不随 Rust 版本变化，同一 bundle 的不同写法也对应同一镜像
+fn bundle_mirror_dir(bundle_path: &str) -> Result<PathBuf, String> {
+    let canonical = fs::canonicalize(bundle_path).unwrap_or_else(|_| PathBuf::from(bundle_path));
+    let digest = Sha256::digest(canonical.to_string_lossy().as_bytes());
+    let hash: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    let stem = Path::new(bundle_path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "bundle".to_string());
+    Ok(get_config_dir()?
+        .join("bundles")
+        .join(format!("{}-{}.git", stem, hash)))
+}
+
+fn sync_bundle_mirror(bundle_path: &str) -> Result<PathBuf, String> {
+    let mirror = bundle_mirror_dir(bundle_path)?;
+    let marker = mirror.join(BUNDLE_SYNC_MARKER);
+
+    let bundle_modified = fs::metadata(bundle_path)
+        .and_then(|m| m.modified())
+        .map_err(|e| format!("读取 bundle 文件失败: {}", e))?;
+    let synced_at = fs::metadata(&marker).and_then(|m| m.modified()).ok();
+    if matches!(synced_at, Some(t) if t >= bundle_modified) {
+        return Ok(mirror);
+    }
+
+    if !mirror.exists() {
+        fs::create_dir_all(&mirror).map_err(|e| format!("创建 bundle 镜像目录失败: {}", e))?;
+        let output = git_command()
+            .args(["init", "--bare", "--quiet"])
+            .current_dir(&mirror)
+            .output()
+            .map_err(|e| format!("初始化 bundle 镜像失败: {}", e))?;
+        if !output.status.success() {
+            return Err(
+                classify_failure("初始化 bundle 镜像失败", bundle_path, &output.stderr).into(),
+            );
+        }
+    }
+
+    eprintln!("导入 bundle: {} -> {}", bundle_path, mirror.display());
+    let output = git_command()
+        .args([
+            "fetch",
+            "--quiet",
+            "--prune",
+            "--update-head-ok",
+            "--end-of-options",
+        ])
+        .arg(bundle_path)
+        .arg("+refs/*:refs/*")
+        .current_dir(&mirror)
+        .output()
+        .map_err(|e| format!("导入 bundle 失败: {}", e))?;
+    if !output.status.success() {
+        return Err(classify_failure("导入 bundle 失败", bundle_path, &output.stderr).into());
+    }
+
+    fs::write(&marker, bundle_path).map_err(|e| format!("写入 bundle 同步标记失败: {}", e))?;
+    Ok(mirror)
+}
+
+/// 所有关联工作区共享的 git 目录，用于识别同一仓库的不同工作区
+pub(crate) fn common_dir(repo_path: &str) -> Option<PathBuf> {
+    let output = git_command()
+        .args(["rev-parse", "--git-common-dir"])
+        .current_dir(repo_path)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let raw = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    // 老版本 git 可能返回相对路径
+    Path::new(repo_path).join(raw).canonicalize().ok()
+}
+
+fn list_worktrees(repo_path: &str) -> Vec<(String, Option<String>)> {
+    let output = match git_command()
+        .args(["worktree", "list", "--porcelain"])
+        .current_dir(repo_path)
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        _ => return Vec::new(),
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut worktrees = Vec::new();
+    let mut current: Option<(String, Option<String>)> = None;
+    for line in stdout.lines() {
+        if let Some(path) = line.strip_prefix("worktree ") {
+            if let Some(wt) = current.take() {
+                worktrees.push(wt);
+            }
+            current = Some((path.to_string(), None));
+        } else if let Some(head) = line.strip_prefix("HEAD ") {
+            if let Some(ref mut wt) = current {
+                wt.1 = Some(head.to_string());
+            }
+        } else if line == "bare" {
+            // 裸仓库本身也会出现在列表中，但没有 HEAD 可供合并
+            current = None;
+        }
+    }
+    if let Some(wt) = current {
+        worktrees.push(wt);
+    }
+    worktrees
+}
+
+fn worktree_heads(repo_path: &str) -> Vec<String> {
+    let mut heads: Vec<String> = list_worktrees(repo_path)
+        .into_iter()
+        .filter_map(|(_, head)| head)
+        .collect();
+    heads.sort();
+    heads.dedup();
+    heads
+}
+
+#[tauri::command]
+pub fn get_repo_identity(path: String) -> Result<RepoIdentity, String> {
+    let repo_path = resolve_repo_path(&path)?;
+
+    let kind = if is_bundle_path(&path) {
+        RepoKind::Bundle
+    } else {
+        let output = git_command()
+            .args(["rev-parse", "--is-bare-repository"])
+            .current_dir(&repo_path)
+            .output()
+            .map_err(|e| format!("无法访问路径: {}", e))?;
+        if !output.status.success() {
+            return Err(classify_failure("不是有效的 Git 仓库", &path, &output.stderr).into());
+        }
+        if String::from_utf8_lossy(&output.stdout).trim() == "true" {
+            RepoKind::Bare
+        } else {
+            RepoKind::WorkTree
+        }
+    };
+
+    let common_dir = common_dir(&repo_path)
+        .map(|p| p.to_string_lossy().to_string())
+        .ok_or_else(|| format!("无法获取仓库目录: {}", path))?;
+    let worktrees = list_worktrees(&repo_path)
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect();
+
+    Ok(RepoIdentity {
+        kind,
+        common_dir,
+        worktrees,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::TempRepo;
+
+    #[test]
+    fn mirror_dir_is_stable_across_path_spellings() {
+        let repo = TempRepo::new("bundle-mirror");
+        repo.write("app.bundle", "");
+        let plain = bundle_mirror_dir(&format!("{}/app.bundle", repo.path())).unwrap();
+        let dotted = bundle_mirror_dir(&format!("{}/./app.bundle", repo.path())).unwrap();
+        assert_eq!(plain, dotted);
+
+        let name = plain.file_name().unwrap().to_string_lossy().to_string();
+        let hash = name
+            .strip_prefix("app-")
+            .and_then(|n| n.strip_suffix(".git"))
+            .unwrap();
+        assert_eq!(hash.len(), 16);
+        assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    #[test]
+    fn worktrees_share_identity() {
+        let repo = TempRepo::new("identity");
+        repo.write("README.md", "hello\n");
+        repo.commit("init");
+        repo.git(&["worktree", "add", "-q", "wt", "-b", "feature"]);
+
+        let main = get_repo_identity(repo.path()).unwrap();
+        let linked = get_repo_identity(format!("{}/wt", repo.path())).unwrap();
+        assert_eq!(main.kind, RepoKind::WorkTree);
+        assert_eq!(main.common_dir, linked.common_dir);
+    }
+}
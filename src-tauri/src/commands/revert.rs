@@ -0,0 +1,275 @@
+use super::dedup::{compute_patch_ids, pipe_through, PatchIds};
+use super::git::{classify_failure, git_command, GitCommit, FIELD_SEPARATOR};
+use super::repo::revision_args;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RevertPair {
+    pub revert_hash: String,
+    pub revert_message: String,
+    pub reverted_hash: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct RevertSummary {
+    pub revert_count: usize,   // 统计范围内本身是回滚的提交数
+    pub reverted_count: usize, // 统计范围内在同一时间段又被回滚的提交数
+    pub pairs: Vec<RevertPair>,
+}
+
+/// 回滚提交 → 被回滚提交
+struct RevertRef {
+    hash: String,
+    message: String,
+    target: String,
+}
+
+const REVERT_MARKER: &str = "This reverts commit ";
+
+/// 时间范围内 message 中带有 `This reverts commit <sha>` 的提交，不按作者筛选，
+/// 别人回滚了自己的提交同样需要体现在报告中
+fn find_revert_messages(path: &str, since: &str, until: &str) -> Result<Vec<RevertRef>, String> {
+    let output = git_command()
+        .args([
+            "log".to_string(),
+            format!("--since={}", since),
+            format!("--until={}", until),
+            format!("--format={sep}%H{sep}%s%n%b", sep = FIELD_SEPARATOR),
+            format!("--grep={}", REVERT_MARKER),
+            "--fixed-strings".to_string(),
+            "--no-merges".to_string(),
+        ])
+        .args(revision_args(path))
+        .current_dir(path)
+        .output()
+        .map_err(|e| format!("查找回滚提交失败: {}", e))?;
+
+    if !output.status.success() {
+        return Err(classify_failure("查找回滚提交失败", path, &output.stderr).into());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut reverts = Vec::new();
+    let mut current: Option<(String, String)> = None;
+    for line in stdout.lines() {
+        if let Some(header) = line.strip_prefix(FIELD_SEPARATOR) {
+            current = header
+                .split_once(FIELD_SEPARATOR)
+                .map(|(hash, message)| (hash.to_string(), message.to_string()));
+        } else if let (Some((hash, message)), Some(idx)) = (&current, line.find(REVERT_MARKER)) {
+            let target: String = line[idx + REVERT_MARKER.len()..]
+                .chars()
+                .take_while(|c| c.is_ascii_hexdigit())
+                .collect();
+            // 过短的缩写无法可靠匹配
+            if target.len() >= 7 {
+                reverts.push(RevertRef {
+                    hash: hash.clone(),
+                    message: message.clone(),
+                    target: target.to_lowercase(),
+                });
+            }
+        }
+    }
+    Ok(reverts)
+}
+
+// message 中的哈希可能是缩写，按前缀匹配完整哈希
+fn match_hash<'a>(target: &str, hashes: impl IntoIterator<Item = &'a String>) -> Option<String> {
+    hashes.into_iter().find(|h| h.starts_with(target)).cloned()
+}
+
+/// 参与回滚配对的提交
+struct Candidate {
+    hash: String,
+    date: String,
+    message: String,
+}
+
+/// 回滚配对：message 中的引用按前缀匹配到列表中的完整哈希（回滚者可以不在列表中），
+/// 没有引用的手工回滚通过反向 patch-id 与列表中较早的提交配对
+fn link_reverts(
+    refs: Vec<RevertRef>,
+    candidates: &[Candidate],
+    patch_ids: &PatchIds,
+) -> Vec<RevertRef> {
+    let hashes: Vec<String> = candidates.iter().map(|c| c.hash.clone()).collect();
+    let mut links: Vec<RevertRef> = refs
+        .into_iter()
+        .map(|mut r| {
+            if let Some(target) = match_hash(&r.target, &hashes) {
+                r.target = target;
+            }
+            r
+        })
+        .collect();
+    let referenced: HashSet<String> = links.iter().map(|r| r.hash.clone()).collect();
+
+    let by_patch_id: HashMap<&str, &Candidate> = candidates
+        .iter()
+        .filter_map(|c| patch_ids.forward.get(&c.hash).map(|id| (id.as_str(), c)))
+        .collect();
+    for candidate in candidates {
+        if referenced.contains(&candidate.hash) {
+            continue;
+        }
+        let original = patch_ids
+            .reverse
+            .get(&candidate.hash)
+            .and_then(|id| by_patch_id.get(id.as_str()));
+        if let Some(original) = original {
+            if original.hash != candidate.hash && original.date <= candidate.date {
+                links.push(RevertRef {
+                    hash: candidate.hash.clone(),
+                    message: candidate.message.clone(),
+                    target: original.hash.clone(),
+                });
+            }
+        }
+    }
+    links
+}
+
+/// 标注提交列表中的回滚关系，配对方式见 link_reverts
+pub(crate) fn pair_reverts(
+    path: &str,
+    since: &str,
+    until: &str,
+    patch_ids: &PatchIds,
+    commits: &mut [GitCommit],
+) {
+    let refs = find_revert_messages(path, since, until).unwrap_or_else(|e| {
+        eprintln!("查找回滚提交失败 ({}): {}", path, e);
+        Vec::new()
+    });
+    let candidates: Vec<Candidate> = commits
+        .iter()
+        .map(|c| Candidate {
+            hash: c.hash.clone(),
+            date: c.date.clone(),
+            message: c.message.clone(),
+        })
+        .collect();
+
+    let listed: HashSet<&String> = candidates.iter().map(|c| &c.hash).collect();
+    let mut reverts: HashMap<String, String> = HashMap::new();
+    let mut reverted_by: HashMap<String, String> = HashMap::new();
+    for link in link_reverts(refs, &candidates, patch_ids) {
+        if listed.contains(&link.target) {
+            reverted_by.insert(link.target.clone(), link.hash.clone());
+        }
+        if listed.contains(&link.hash) {
+            reverts.insert(link.hash, link.target);
+        }
+    }
+
+    for commit in commits.iter_mut() {
+        commit.reverts = reverts.get(&commit.hash).cloned();
+        commit.reverted_by = reverted_by.get(&commit.hash).cloned();
+    }
+}
+
+/// 统计范围内（counted 为按作者筛选后的全部提交）的回滚情况，与 pair_reverts 使用相同的配对方式，
+/// 只有被回滚的提交也在范围内时才记为一对
+pub(crate) fn summarize_reverts(
+    path: &str,
+    since: &str,
+    until: &str,
+    counted: &HashSet<String>,
+    summary: &mut RevertSummary,
+) -> Result<(), String> {
+    let refs = find_revert_messages(path, since, until)?;
+    let hashes: Vec<&str> = counted.iter().map(String::as_str).collect();
+    let candidates = describe_commits(path, &hashes)?;
+    let patch_ids = compute_patch_ids(path, &hashes)?;
+
+    let mut reverted: HashSet<String> = HashSet::new();
+    for link in link_reverts(refs, &candidates, &patch_ids) {
+        if counted.contains(&link.hash) {
+            summary.revert_count += 1;
+        }
+        if counted.contains(&link.target) && reverted.insert(link.target.clone()) {
+            summary.pairs.push(RevertPair {
+                revert_hash: link.hash,
+                revert_message: link.message,
+                reverted_hash: link.target,
+            });
+        }
+    }
+    summary.reverted_count += reverted.len();
+    Ok(())
+}
+
+// 一次 log --stdin 读取提交时间和标题，时间格式与 GitCommit.date 相同
+fn describe_commits(path: &str, hashes: &[&str]) -> Result<Vec<Candidate>, String> {
+    if hashes.is_empty() {
+        return Ok(Vec::new());
+    }
+    let format = format!("--format=%H{sep}%ai{sep}%s", sep = FIELD_SEPARATOR);
+    let input: String = hashes.iter().map(|h| format!("{}\n", h)).collect();
+    let output = pipe_through(
+        path,
+        &["log", "--no-walk=unsorted", "--stdin", format.as_str()],
+        input.into_bytes(),
+    )?;
+    Ok(String::from_utf8_lossy(&output)
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, FIELD_SEPARATOR);
+            Some(Candidate {
+                hash: parts.next()?.to_string(),
+                date: parts.next()?.to_string(),
+                message: parts.next()?.to_string(),
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::TempRepo;
+
+    #[test]
+    fn summary_includes_manual_reverts() {
+        let repo = TempRepo::new("revert-summary");
+        repo.write("a.txt", "a\n");
+        repo.write("b.txt", "b\n");
+        repo.commit_at("init", "2024-01-01 10:00:00");
+        repo.write("a.txt", "a\nfeature\n");
+        repo.commit_at("add feature", "2024-01-02 10:00:00");
+        repo.write("b.txt", "b\ntweak\n");
+        repo.commit_at("tweak", "2024-01-03 10:00:00");
+        repo.write("b.txt", "b\n");
+        repo.commit_at("undo tweak", "2024-01-04 10:00:00");
+        repo.git(&["revert", "--no-edit", "HEAD~2"]);
+
+        let hashes = repo.rev_list("HEAD");
+        let counted: HashSet<String> = hashes.iter().cloned().collect();
+        let mut summary = RevertSummary::default();
+        summarize_reverts(
+            &repo.path(),
+            "2000-01-01",
+            "2099-12-31",
+            &counted,
+            &mut summary,
+        )
+        .unwrap();
+
+        assert_eq!(summary.revert_count, 2);
+        assert_eq!(summary.reverted_count, 2);
+        let mut pairs: Vec<(&str, &str)> = summary
+            .pairs
+            .iter()
+            .map(|p| (p.revert_hash.as_str(), p.reverted_hash.as_str()))
+            .collect();
+        pairs.sort();
+        let mut expected = vec![
+            (hashes[0].as_str(), hashes[3].as_str()),
+            (hashes[1].as_str(), hashes[2].as_str()),
+        ];
+        expected.sort();
+        assert_eq!(pairs, expected);
+    }
+}
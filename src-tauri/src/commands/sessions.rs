@@ -0,0 +1,292 @@
+use super::config::{load_config, ProjectConfig};
+use super::git::{
+    classify_failure, git_command, parse_commit_date, validate_authors, validate_date,
+    FIELD_SEPARATOR,
+};
+use super::repo::{resolve_repo_path, revision_args};
+use chrono::{DateTime, Duration, FixedOffset};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkSessionReport {
+    pub total_hours: f64,
+    pub sessions: Vec<WorkSession>,
+    pub days: Vec<DayHours>,
+    pub projects: Vec<ProjectHours>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkSession {
+    pub start: String,
+    pub end: String,
+    pub commit_count: usize,
+    pub hours: f64,
+    pub projects: Vec<ProjectHours>,
+    pub subjects: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DayHours {
+    pub date: String,
+    pub hours: f64,
+    pub projects: Vec<ProjectHours>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProjectHours {
+    pub name: String,
+    pub hours: f64,
+}
+
+/// 带项目归属的提交，工时和工时表都基于它计算
+pub(crate) struct TimedCommit {
+    pub project: String,
+    pub time: DateTime<FixedOffset>,
+    pub subject: String,
+}
+
+/// 会话内每段间隔的分钟数记到后一个提交所属的项目，lead-in 记到会话首个提交的项目
+pub(crate) struct SessionSpan {
+    pub start: DateTime<FixedOffset>, // 含 lead-in，早于会话首个提交
+    pub first_commit: DateTime<FixedOffset>,
+    pub end: DateTime<FixedOffset>,
+    pub commit_count: usize,
+    pub minutes: BTreeMap<String, i64>,
+    pub subjects: Vec<(String, String)>, // (项目, 提交标题)
+}
+
+impl SessionSpan {
+    /// 会话归属的日期：首个提交在其时区下的日期。start 含 lead-in，
+    /// 00:10 开始的会话按 start 会被算到前一天
+    pub fn day(&self) -> String {
+        self.first_commit.format("%Y-%m-%d").to_string()
+    }
+}
+
+#[tauri::command]
+pub fn get_work_sessions(
+    since: String,
+    until: String,
+    gap_minutes: Option<u32>,
+    lead_in_minutes: Option<u32>,
+) -> Result<WorkSessionReport, String> {
+    validate_date(&since)?;
+    validate_date(&until)?;
+
+    let config = load_config()?;
+    let gap = gap_minutes.unwrap_or(config.work_sessions.gap_minutes);
+    let lead_in = lead_in_minutes.unwrap_or(config.work_sessions.lead_in_minutes);
+    if gap == 0 {
+        return Err("会话间隔阈值必须大于 0".to_string());
+    }
+
+    let commits = collect_project_commits(&config.projects, &since, &until);
+    let spans = build_sessions(commits, gap, lead_in);
+
+    let mut days: BTreeMap<String, BTreeMap<String, i64>> = BTreeMap::new();
+    let mut projects: BTreeMap<String, i64> = BTreeMap::new();
+    let mut sessions = Vec::new();
+
+    for span in &spans {
+        // 跨午夜的会话整体计入开始那天，与工时表的填报习惯一致
+        let day = days.entry(span.day()).or_default();
+        for (project, minutes) in &span.minutes {
+            *day.entry(project.clone()).or_insert(0) += minutes;
+            *projects.entry(project.clone()).or_insert(0) += minutes;
+        }
+        sessions.push(WorkSession {
+            start: span.start.format("%Y-%m-%d %H:%M:%S %z").to_string(),
+            end: span.end.format("%Y-%m-%d %H:%M:%S %z").to_string(),
+            commit_count: span.commit_count,
+            hours: minutes_to_hours(span.minutes.values().sum()),
+            projects: to_project_hours(&span.minutes),
+            subjects: span
+                .subjects
+                .iter()
+                .map(|(_, subject)| subject.clone())
+                .collect(),
+        });
+    }
+
+    let total_minutes: i64 = projects.values().sum();
+
+    Ok(WorkSessionReport {
+        total_hours: minutes_to_hours(total_minutes),
+        sessions,
+        days: days
+            .into_iter()
+            .map(|(date, per_project)| DayHours {
+                date,
+                hours: minutes_to_hours(per_project.values().sum()),
+                projects: to_project_hours(&per_project),
+            })
+            .collect(),
+        projects: to_project_hours(&projects),
+    })
+}
+
+pub(crate) fn build_sessions(
+    mut commits: Vec<TimedCommit>,
+    gap_minutes: u32,
+    lead_in_minutes: u32,
+) -> Vec<SessionSpan> {
+    // 多个项目的提交合并到同一时间线，避免并行项目的工时重复计算
+    commits.sort_by_key(|c| c.time);
+
+    let gap = Duration::minutes(gap_minutes as i64);
+    let lead_in = lead_in_minutes as i64;
+    let mut spans: Vec<SessionSpan> = Vec::new();
+
+    for commit in commits {
+        match spans.last_mut() {
+            Some(span) if commit.time - span.end <= gap => {
+                let minutes = (commit.time - span.end).num_minutes();
+                *span.minutes.entry(commit.project.clone()).or_insert(0) += minutes;
+                span.end = commit.time;
+                span.commit_count += 1;
+                span.subjects.push((commit.project, commit.subject));
+            }
+            _ => {
+                let mut minutes = BTreeMap::new();
+                minutes.insert(commit.project.clone(), lead_in);
+                spans.push(SessionSpan {
+                    start: commit.time - Duration::minutes(lead_in),
+                    first_commit: commit.time,
+                    end: commit.time,
+                    commit_count: 1,
+                    minutes,
+                    subjects: vec![(commit.project, commit.subject)],
+                });
+            }
+        }
+    }
+
+    spans
+}
+
+/// 按项目配置的作者和已启用的子模块收集提交，子模块提交归属父项目
+pub(crate) fn collect_project_commits(
+    projects: &[ProjectConfig],
+    since: &str,
+    until: &str,
+) -> Vec<TimedCommit> {
+    let mut commits = Vec::new();
+
+    for project in projects {
+        let repo_paths = std::iter::once(project.repo_path.clone()).chain(
+            project
+                .submodules
+                .iter()
+                .filter(|s| s.enabled)
+                .map(|s| s.path.clone()),
+        );
+
+        for repo_path in repo_paths {
+            let resolved = match resolve_repo_path(&repo_path) {
+                Ok(resolved) => resolved,
+                Err(e) => {
+                    eprintln!("项目路径无效 ({}): {}", repo_path, e);
+                    continue;
+                }
+            };
+            match fetch_timed_commits(&resolved, since, until, &project.authors) {
+                Ok(entries) => {
+                    commits.extend(entries.into_iter().map(|(time, subject)| TimedCommit {
+                        project: project.name.clone(),
+                        time,
+                        subject,
+                    }));
+                }
+                Err(e) => eprintln!("获取提交时间失败 ({}): {}", repo_path, e),
+            }
+        }
+    }
+
+    commits
+}
+
+fn fetch_timed_commits(
+    path: &str,
+    since: &str,
+    until: &str,
+    authors: &[String],
+) -> Result<Vec<(DateTime<FixedOffset>, String)>, String> {
+    let mut args = vec![
+        "log".to_string(),
+        format!("--since={}", since),
+        format!("--until={}", until),
+        format!("--format=%ai{}%s", FIELD_SEPARATOR),
+        "--no-merges".to_string(),
+    ];
+    validate_authors(authors)?;
+    for author in authors {
+        args.push(format!("--author={}", author));
+    }
+    args.extend(revision_args(path));
+
+    let output = git_command()
+        .args(&args)
+        .current_dir(path)
+        .output()
+        .map_err(|e| format!("获取 Git 日志失败: {}", e))?;
+
+    if !output.status.success() {
+        return Err(classify_failure("Git log 执行失败", path, &output.stderr).into());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            let (date, subject) = line.split_once(FIELD_SEPARATOR)?;
+            Some((parse_commit_date(date)?, subject.trim().to_string()))
+        })
+        .collect())
+}
+
+pub(crate) fn minutes_to_hours(minutes: i64) -> f64 {
+    (minutes as f64 / 60.0 * 100.0).round() / 100.0
+}
+
+fn to_project_hours(minutes: &BTreeMap<String, i64>) -> Vec<ProjectHours> {
+    minutes
+        .iter()
+        .map(|(name, m)| ProjectHours {
+            name: name.clone(),
+            hours: minutes_to_hours(*m),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commit(time: &str, subject: &str) -> TimedCommit {
+        TimedCommit {
+            project: "app".to_string(),
+            time: parse_commit_date(time).unwrap(),
+            subject: subject.to_string(),
+        }
+    }
+
+    #[test]
+    fn books_sessions_on_first_commit_day() {
+        let spans = build_sessions(
+            vec![
+                commit("2024-03-05 00:10:00 +0800", "just after midnight"),
+                commit("2024-03-05 23:50:00 +0800", "late"),
+                commit("2024-03-06 00:30:00 +0800", "across midnight"),
+            ],
+            120,
+            30,
+        );
+
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].start.format("%Y-%m-%d").to_string(), "2024-03-04");
+        assert_eq!(spans[0].day(), "2024-03-05");
+        assert_eq!(spans[1].day(), "2024-03-05");
+        assert_eq!(spans[1].minutes["app"], 30 + 40);
+    }
+}
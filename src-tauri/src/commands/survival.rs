@@ -0,0 +1,309 @@
+use super::git::{
+    classify_failure, component_matcher, git_command, parse_numstat_z, validate_authors,
+    validate_date, validate_path, FIELD_SEPARATOR,
+};
+use super::repo::{resolve_repo_path, revision_args};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SurvivalReport {
+    pub lines_added: usize,
+    pub lines_surviving: usize,
+    pub survival_rate: f64,
+    pub files_blamed: usize,
+    pub files_skipped: usize, // 超出 blame 数量上限、未计入统计的文件
+    pub repos: Vec<SurvivalEntry>,
+    pub components: Vec<SurvivalEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SurvivalEntry {
+    pub name: String,
+    pub lines_added: usize,
+    pub lines_surviving: usize,
+    pub survival_rate: f64,
+}
+
+// 每个文件都要单独执行一次 blame，按新增行数取前若干个文件
+const MAX_BLAME_FILES: usize = 300;
+
+#[derive(Default)]
+struct SurvivalAccumulator {
+    added: usize,
+    surviving: usize,
+}
+
+impl SurvivalAccumulator {
+    fn into_entry(self, name: String) -> SurvivalEntry {
+        SurvivalEntry {
+            name,
+            lines_added: self.added,
+            lines_surviving: self.surviving,
+            survival_rate: rate(self.surviving, self.added),
+        }
+    }
+}
+
+/// 时间范围内新增的代码在 HEAD 中仍然保留的行数。
+/// 反复修改同一段代码会抬高 churn，留存行数更能反映实际产出
+#[tauri::command]
+pub fn get_code_survival(
+    path: String,
+    since: String,
+    until: String,
+    authors: Vec<String>,
+    include_submodules: Vec<String>,
+) -> Result<SurvivalReport, String> {
+    let repo_path = resolve_repo_path(&path)?;
+    validate_date(&since)?;
+    validate_date(&until)?;
+    validate_authors(&authors)?;
+
+    let mut repos = vec![(path.clone(), repo_path.clone())];
+    for sub_path in include_submodules {
+        if let Err(e) = validate_path(&sub_path) {
+            eprintln!("子模块路径无效 ({}): {}", sub_path, e);
+            continue;
+        }
+        repos.push((sub_path.clone(), sub_path));
+    }
+
+    // 先收集所有仓库的候选文件，再统一按新增行数截取，避免主仓库占满上限
+    let mut candidates: Vec<(usize, AddedFile)> = Vec::new();
+    let mut period_commits: Vec<HashSet<String>> = Vec::new();
+    for (i, (_, repo)) in repos.iter().enumerate() {
+        match fetch_added_lines(repo, &since, &until, &authors) {
+            Ok((hashes, files)) => {
+                candidates.extend(files.into_iter().map(|f| (i, f)));
+                period_commits.push(hashes);
+            }
+            Err(e) if i == 0 => return Err(e),
+            Err(e) => {
+                eprintln!("子模块日志获取失败 ({}): {}", repo, e);
+                period_commits.push(HashSet::new());
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.1.added.cmp(&a.1.added).then(a.1.path.cmp(&b.1.path)));
+    let files_skipped = candidates.len().saturating_sub(MAX_BLAME_FILES);
+    candidates.truncate(MAX_BLAME_FILES);
+
+    let mut total = SurvivalAccumulator::default();
+    let mut by_repo: Vec<SurvivalAccumulator> = repos.iter().map(|_| Default::default()).collect();
+    let mut by_component: BTreeMap<String, SurvivalAccumulator> = BTreeMap::new();
+
+    for (i, (project_path, repo)) in repos.iter().enumerate() {
+        let files: Vec<&AddedFile> = candidates
+            .iter()
+            .filter(|(r, _)| *r == i)
+            .map(|(_, f)| f)
+            .collect();
+        if files.is_empty() {
+            continue;
+        }
+        let head_files = list_head_files(repo);
+        let (matcher, prefix) = component_matcher(project_path, repo);
+
+        for file in files {
+            // HEAD 中已不存在的文件（被删除或之后改名）视为全部未保留
+            let surviving = if head_files.contains(&file.path) {
+                blame_surviving(repo, &file.path, &period_commits[i]).unwrap_or_else(|e| {
+                    eprintln!("blame 失败 ({}: {}): {}", repo, file.path, e);
+                    0
+                })
+            } else {
+                0
+            };
+            // 行数统计来自不同命令，以 diff 中的新增行数为上限
+            let surviving = surviving.min(file.added);
+
+            total.added += file.added;
+            total.surviving += surviving;
+            by_repo[i].added += file.added;
+            by_repo[i].surviving += surviving;
+            for component in matcher.resolve(&file.path, &prefix) {
+                let entry = by_component.entry(component).or_default();
+                entry.added += file.added;
+                entry.surviving += surviving;
+            }
+        }
+    }
+
+    let mut components: Vec<SurvivalEntry> = by_component
+        .into_iter()
+        .map(|(name, acc)| acc.into_entry(name))
+        .collect();
+    components.sort_by(|a, b| b.lines_added.cmp(&a.lines_added).then(a.name.cmp(&b.name)));
+
+    Ok(SurvivalReport {
+        lines_added: total.added,
+        lines_surviving: total.surviving,
+        survival_rate: rate(total.surviving, total.added),
+        files_blamed: candidates.len(),
+        files_skipped,
+        repos: repos
+            .into_iter()
+            .zip(by_repo)
+            .filter(|(_, acc)| acc.added > 0)
+            .map(|((name, _), acc)| acc.into_entry(name))
+            .collect(),
+        components,
+    })
+}
+
+fn rate(surviving: usize, added: usize) -> f64 {
+    if added == 0 {
+        0.0
+    } else {
+        surviving as f64 / added as f64
+    }
+}
+
+struct AddedFile {
+    path: String,
+    added: usize,
+}
+
+/// 时间范围内作者的提交哈希，以及每个文件累计新增的行数（不含二进制文件）
+fn fetch_added_lines(
+    path: &str,
+    since: &str,
+    until: &str,
+    authors: &[String],
+) -> Result<(HashSet<String>, Vec<AddedFile>), String> {
+    let mut args = vec![
+        "log".to_string(),
+        format!("--since={}", since),
+        format!("--until={}", until),
+        format!("--pretty=format:{}%H", FIELD_SEPARATOR),
+        "--numstat".to_string(),
+        "-z".to_string(), // 保留原始路径，与 ls-tree -z 的结果一致
+        "--no-renames".to_string(),
+        "--no-merges".to_string(),
+    ];
+    for author in authors {
+        args.push(format!("--author={}", author));
+    }
+    args.extend(revision_args(path));
+
+    let output = git_command()
+        .args(&args)
+        .current_dir(path)
+        .output()
+        .map_err(|e| format!("获取 Git 变更统计失败: {}", e))?;
+    if !output.status.success() {
+        return Err(classify_failure("Git log 执行失败", path, &output.stderr).into());
+    }
+
+    let mut hashes = HashSet::new();
+    let mut added: HashMap<String, usize> = HashMap::new();
+    for (hash, entries) in parse_numstat_z(&output.stdout) {
+        hashes.insert(hash);
+        for entry in entries {
+            // 二进制文件的行数为 "-"，无法 blame
+            if let Some(count) = entry.added.filter(|&c| c > 0) {
+                *added.entry(entry.path).or_default() += count;
+            }
+        }
+    }
+
+    let files = added
+        .into_iter()
+        .map(|(path, added)| AddedFile { path, added })
+        .collect();
+    Ok((hashes, files))
+}
+
+fn list_head_files(repo_path: &str) -> HashSet<String> {
+    let output = match git_command()
+        .args(["ls-tree", "-r", "-z", "--name-only", "HEAD"])
+        .current_dir(repo_path)
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        _ => return HashSet::new(),
+    };
+    output
+        .stdout
+        .split(|&b| b == 0)
+        .filter(|name| !name.is_empty())
+        .map(|name| String::from_utf8_lossy(name).to_string())
+        .collect()
+}
+
+/// HEAD 中由时间范围内提交引入的行数。
+/// 使用 -w 忽略空白改动，他人仅调整缩进或格式化时仍算作原作者的代码；
+/// blame 默认执行 textconv，必须关闭（见 git_command）
+fn blame_surviving(
+    repo_path: &str,
+    file: &str,
+    commits: &HashSet<String>,
+) -> Result<usize, String> {
+    let output = git_command()
+        .args([
+            "blame",
+            "--porcelain",
+            "--no-textconv",
+            "-w",
+            "HEAD",
+            "--",
+            file,
+        ])
+        .current_dir(repo_path)
+        .output()
+        .map_err(|e| format!("执行 git blame 失败: {}", e))?;
+    if !output.status.success() {
+        return Err(classify_failure("执行 git blame 失败", repo_path, &output.stderr).into());
+    }
+
+    // porcelain 格式中每行代码前都有一行 `<sha> <原行号> <最终行号> [<行数>]`，
+    // 其余元数据行以字段名开头，代码内容以制表符开头
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.starts_with('\t'))
+        .filter_map(|line| {
+            let mut fields = line.split(' ');
+            let hash = fields.next()?;
+            let is_header = (hash.len() == 40 || hash.len() == 64)
+                && hash.chars().all(|c| c.is_ascii_hexdigit())
+                && fields.count() >= 2;
+            is_header.then_some(hash)
+        })
+        .filter(|hash| commits.contains(*hash))
+        .count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::TempRepo;
+
+    #[test]
+    fn numstat_paths_are_not_quoted() {
+        let raw = "\x1eabc\n2\t0\t订单.txt\0\x001\t1\tplain.txt\0-\t-\t图.png\0";
+        let commits = parse_numstat_z(raw.as_bytes());
+        assert_eq!(commits.len(), 1);
+        let paths: Vec<&str> = commits[0].1.iter().map(|e| e.path.as_str()).collect();
+        assert_eq!(paths, ["订单.txt", "plain.txt", "图.png"]);
+        assert_eq!(commits[0].1[2].added, None);
+    }
+
+    #[test]
+    fn counts_surviving_lines_of_non_ascii_files() {
+        let repo = TempRepo::new("survival-non-ascii");
+        repo.write("订单.txt", "a\nb\nc\n");
+        repo.commit("add");
+
+        let report = get_code_survival(
+            repo.path(),
+            "2000-01-01".to_string(),
+            "2099-12-31".to_string(),
+            vec![],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(report.lines_added, 3);
+        assert_eq!(report.lines_surviving, 3);
+    }
+}
@@ -0,0 +1,460 @@
+use super::automation::{automation_excluded, detect_automation, flag_automated};
+use super::git::{validate_path, GitCommit, GitFile, INHERITED_ENV};
+use super::vcs::VcsBackend;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime};
+use std::path::Path;
+use std::process::Command;
+
+const FILE_URL_PREFIX: &str = "file://";
+
+/// 通过 svn 命令行读取本地工作副本或 file:// 仓库，修订映射为与 git 一致的提交结构
+pub(crate) struct SvnBackend {
+    path: String,
+}
+
+impl SvnBackend {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+        }
+    }
+
+    fn run(&self, context: &str, args: &[&str]) -> Result<String, String> {
+        let output = svn_command()
+            .args(args)
+            .arg(peg_target(&self.path))
+            .output()
+            .map_err(|e| format!("{}: 无法执行 svn（{}）", context, e))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("{}: {}", context, stderr.trim()));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// 时间范围内的修订，按作者筛选后按时间倒序返回，verbose 时附带变更路径
+    fn entries(
+        &self,
+        since: &str,
+        until: &str,
+        authors: &[String],
+        verbose: bool,
+    ) -> Result<Vec<GitCommit>, String> {
+        // {since} 解析为该时刻之前的最后一个修订，范围两端再按提交时间精确过滤
+        let range = format!("{{{}}}:{{{}}}", until, since);
+        let mut args = vec!["log", "--xml", "-r", range.as_str()];
+        if verbose {
+            args.push("-v");
+        }
+        let stdout = self.run("SVN 日志获取失败", &args)?;
+
+        let prefix = if verbose {
+            self.relative_root()
+        } else {
+            String::new()
+        };
+        let since = parse_bound(since, false);
+        let until = parse_bound(until, true);
+        let mut commits: Vec<GitCommit> = parse_svn_log(&stdout, &prefix)
+            .into_iter()
+            .filter(|c| match local_time(&c.date) {
+                Some(date) => {
+                    !matches!(since, Some(s) if date < s) && !matches!(until, Some(u) if date > u)
+                }
+                None => true,
+            })
+            // 与 git --author 一致，按子串匹配作者
+            .filter(|c| authors.is_empty() || authors.iter().any(|a| c.author.contains(a)))
+            .collect();
+        for commit in commits.iter_mut() {
+            commit.date = format_date(&commit.date);
+        }
+        commits.sort_by(|a, b| b.date.cmp(&a.date));
+        Ok(commits)
+    }
+
+    /// 工作副本在仓库中的位置，如 trunk/，变更路径去掉该前缀后与工作副本内的相对路径一致
+    fn relative_root(&self) -> String {
+        let url = self
+            .run("", &["info", "--show-item", "relative-url"])
+            .unwrap_or_default();
+        root_prefix(&url)
+    }
+}
+
+// relative-url 形如 ^/trunk/%E6%96%87%E6%A1%A3，转为 trunk/文档/
+fn root_prefix(relative_url: &str) -> String {
+    let root = percent_decode(
+        relative_url
+            .trim()
+            .trim_start_matches('^')
+            .trim_matches('/'),
+    );
+    if root.is_empty() {
+        root
+    } else {
+        format!("{}/", root)
+    }
+}
+
+/// LC_ALL=C 保证错误信息不随系统语言变化，日志本身为 UTF-8 的 XML
+pub(crate) fn svn_command() -> Command {
+    let mut cmd = Command::new("svn");
+    cmd.env_clear();
+    for key in INHERITED_ENV {
+        if let Some(value) = std::env::var_os(key) {
+            cmd.env(key, value);
+        }
+    }
+    cmd.env("LC_ALL", "C").arg("--non-interactive");
+    cmd
+}
+
+// 路径中的 @ 会被当作锚定修订，末尾追加一个 @ 使其按字面处理
+fn peg_target(path: &str) -> String {
+    format!("{}@", path)
+}
+
+pub(crate) fn is_svn_url(path: &str) -> bool {
+    path.starts_with(FILE_URL_PREFIX)
+}
+
+/// 只接受本地工作副本和 file:// 仓库，不访问远程服务器
+pub(crate) fn validate_svn_target(path: &str) -> Result<(), String> {
+    match path.strip_prefix(FILE_URL_PREFIX) {
+        Some(local) => {
+            if !Path::new(&percent_decode(local)).is_dir() {
+                return Err(format!("SVN 仓库不存在: {}", path));
+            }
+            Ok(())
+        }
+        None => validate_path(path),
+    }
+}
+
+pub(crate) fn is_svn_repository(path: &str) -> bool {
+    validate_svn_target(path).is_ok()
+        && svn_command()
+            .args(["info", "--show-item", "revision"])
+            .arg(peg_target(path))
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false)
+}
+
+impl VcsBackend for SvnBackend {
+    fn log(
+        &self,
+        since: &str,
+        until: &str,
+        authors: &[String],
+        limit: usize,
+    ) -> Result<Vec<GitCommit>, String> {
+        let mut commits = self.entries(since, until, authors, true)?;
+        commits.truncate(limit);
+        flag_automated(&mut commits);
+        Ok(commits)
+    }
+
+    fn count_commits(
+        &self,
+        since: &str,
+        until: &str,
+        authors: &[String],
+        automated: &mut usize,
+    ) -> Result<usize, String> {
+        let commits = self.entries(since, until, authors, false)?;
+        let total = commits.len();
+        if !automation_excluded(&self.path) {
+            return Ok(total);
+        }
+        let skipped = commits
+            .iter()
+            .filter(|c| detect_automation(&c.author, &c.email, &c.message).is_some())
+            .count();
+        *automated += skipped;
+        Ok(total - skipped)
+    }
+
+    fn authors(&self) -> Result<Vec<String>, String> {
+        let stdout = self.run("获取提交人列表失败", &["log", "--xml", "-q"])?;
+        let mut authors: Vec<String> = parse_svn_log(&stdout, "")
+            .into_iter()
+            .map(|c| c.author)
+            .filter(|a| !a.is_empty())
+            .collect();
+        authors.sort();
+        authors.dedup();
+        Ok(authors)
+    }
+}
+
+// svn 日期为 UTC，如 2024-01-15T02:00:00.123456Z
+fn local_time(date: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(date)
+        .ok()
+        .map(|d| d.with_timezone(&Local).naive_local())
+}
+
+/// 转为与 git `%ai` 相同的本地时间格式
+fn format_date(date: &str) -> String {
+    DateTime::parse_from_rfc3339(date)
+        .map(|d| {
+            d.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M:%S %z")
+                .to_string()
+        })
+        .unwrap_or_else(|_| date.to_string())
+}
+
+// 前端传入 YYYY-MM-DD HH:mm:ss 格式的本地时间，只有日期时取当天的起止时刻
+fn parse_bound(value: &str, end_of_day: bool) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M"))
+        .ok()
+        .or_else(|| {
+            let day = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+            if end_of_day {
+                day.and_hms_opt(23, 59, 59)
+            } else {
+                day.and_hms_opt(0, 0, 0)
+            }
+        })
+}
+
+/// 解析 `svn log --xml` 的输出。变更路径为仓库内的绝对路径，去掉 prefix 后作为文件路径，
+/// 目录的变更不计入文件；R（替换）按修改处理
+fn parse_svn_log(xml: &str, prefix: &str) -> Vec<GitCommit> {
+    let mut commits = Vec::new();
+    for entry in xml.split("<logentry").skip(1) {
+        let Some(head_end) = entry.find('>') else {
+            continue;
+        };
+        let Some(revision) = attribute(&entry[..head_end], "revision") else {
+            continue;
+        };
+        let message = element_text(entry, "msg").unwrap_or_default();
+
+        let mut files = Vec::new();
+        let mut rest = entry;
+        while let Some(start) = rest.find("<path") {
+            rest = &rest[start + "<path".len()..];
+            // 跳过 <paths> 容器
+            if !rest.starts_with(|c: char| c.is_whitespace() || c == '>') {
+                continue;
+            }
+            let (Some(tag_end), Some(close)) = (rest.find('>'), rest.find("</path>")) else {
+                break;
+            };
+            let attrs = &rest[..tag_end];
+            let text = unescape(&rest[tag_end + 1..close]);
+            rest = &rest[close..];
+            if attribute(attrs, "kind").as_deref() == Some("dir") {
+                continue;
+            }
+            let status = match attribute(attrs, "action").as_deref() {
+                Some("A") => "A",
+                Some("D") => "D",
+                _ => "M",
+            };
+            let path = text.trim_start_matches('/');
+            files.push(GitFile {
+                status: status.to_string(),
+                path: path.strip_prefix(prefix).unwrap_or(path).to_string(),
+                components: Vec::new(),
+                web_url: None,
+                asset: None,
+                size: None,
+            });
+        }
+
+        commits.push(GitCommit {
+            hash: format!("r{}", revision),
+            author: element_text(entry, "author").unwrap_or_default(),
+            date: element_text(entry, "date").unwrap_or_default(),
+            message: message.lines().next().unwrap_or("").trim().to_string(),
+            files,
+            ..Default::default()
+        });
+    }
+    commits
+}
+
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let key = format!("{}=\"", name);
+    let start = tag.find(&key)? + key.len();
+    let end = tag[start..].find('"')?;
+    Some(unescape(&tag[start..start + end]))
+}
+
+fn element_text(entry: &str, name: &str) -> Option<String> {
+    let open = format!("<{}>", name);
+    let close = format!("</{}>", name);
+    let start = entry.find(&open)? + open.len();
+    let end = entry[start..].find(&close)?;
+    Some(unescape(&entry[start..start + end]))
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+// svn info 输出的 URL 经过百分号编码
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        // from_str_radix 接受 +/- 符号，需逐字节确认是十六进制数字
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1..i + 3].iter().all(u8::is_ascii_hexdigit)
+        {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or_default();
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<log>
+<logentry
+   revision="42">
+<author>alice</author>
+<date>2024-01-15T02:00:00.123456Z</date>
+<paths>
+<path
+   prop-mods="false"
+   text-mods="false"
+   kind="dir"
+   action="A">/trunk/src</path>
+<path
+   prop-mods="false"
+   text-mods="true"
+   kind="file"
+   action="A">/trunk/src/main.rs</path>
+<path
+   prop-mods="false"
+   text-mods="true"
+   kind="file"
+   action="R"
+   copyfrom-path="/branches/dev/lib.rs"
+   copyfrom-rev="40">/trunk/src/lib.rs</path>
+<path
+   prop-mods="false"
+   text-mods="false"
+   kind="file"
+   action="D">/trunk/R&amp;D &lt;old&gt;.txt</path>
+<path
+   prop-mods="false"
+   text-mods="true"
+   kind="file"
+   action="M">/branches/dev/notes.md</path>
+</paths>
+<msg>Fix &lt;path&gt; handling &amp; cleanup
+second line</msg>
+</logentry>
+<logentry
+   revision="41">
+<author>bob</author>
+<date>2024-01-14T09:30:00.000000Z</date>
+<msg></msg>
+</logentry>
+</log>
+"#;
+
+    #[test]
+    fn parses_verbose_log() {
+        let commits = parse_svn_log(LOG, "trunk/");
+        assert_eq!(commits.len(), 2);
+
+        let commit = &commits[0];
+        assert_eq!(commit.hash, "r42");
+        assert_eq!(commit.author, "alice");
+        assert_eq!(commit.date, "2024-01-15T02:00:00.123456Z");
+        assert_eq!(commit.message, "Fix <path> handling & cleanup");
+        let files: Vec<(&str, &str)> = commit
+            .files
+            .iter()
+            .map(|f| (f.status.as_str(), f.path.as_str()))
+            .collect();
+        assert_eq!(
+            files,
+            [
+                ("A", "src/main.rs"),
+                ("M", "src/lib.rs"),
+                ("D", "R&D <old>.txt"),
+                ("M", "branches/dev/notes.md"),
+            ]
+        );
+
+        assert_eq!(commits[1].hash, "r41");
+        assert!(commits[1].files.is_empty());
+    }
+
+    #[test]
+    fn strips_relative_root() {
+        assert_eq!(root_prefix("^/trunk\n"), "trunk/");
+        assert_eq!(root_prefix("^/"), "");
+        assert_eq!(
+            root_prefix("^/branches/%E6%96%87%E6%A1%A3"),
+            "branches/文档/"
+        );
+    }
+
+    #[test]
+    fn decodes_only_hex_escapes() {
+        assert_eq!(percent_decode("a%20b%2Fc"), "a b/c");
+        assert_eq!(percent_decode("%+1"), "%+1");
+        assert_eq!(percent_decode("%-1x"), "%-1x");
+        assert_eq!(percent_decode("100%"), "100%");
+        assert_eq!(percent_decode("%4"), "%4");
+    }
+
+    #[test]
+    fn unescapes_entities_once() {
+        assert_eq!(
+            unescape("a &lt;b&gt; &quot;c&quot; &apos;d&apos;"),
+            "a <b> \"c\" 'd'"
+        );
+        assert_eq!(unescape("&amp;lt;"), "&lt;");
+    }
+
+    #[test]
+    fn parses_bounds() {
+        let at = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap();
+        assert_eq!(
+            parse_bound("2024-01-15", false),
+            Some(at("2024-01-15 00:00:00"))
+        );
+        assert_eq!(
+            parse_bound("2024-01-15", true),
+            Some(at("2024-01-15 23:59:59"))
+        );
+        assert_eq!(
+            parse_bound(" 2024-01-15 08:30:15 ", true),
+            Some(at("2024-01-15 08:30:15"))
+        );
+        assert_eq!(
+            parse_bound("2024-01-15 08:30", false),
+            Some(at("2024-01-15 08:30:00"))
+        );
+        assert_eq!(parse_bound("yesterday", false), None);
+    }
+}
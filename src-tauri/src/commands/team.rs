@@ -0,0 +1,387 @@
+use super::automation::automation_excluded;
+use super::component::ComponentSummary;
+use super::git::{
+    author_filters, classify_failure, co_authors_counted, component_matcher, counted_commits,
+    fetch_commits_by_hash, git_command, parse_fields_numstat_z, tag_components, validate_authors,
+    validate_date, validate_path, GitCommit,
+};
+use super::repo::{resolve_repo_path, revision_args};
+use super::ticket::extract_tickets;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TeamBreakdown {
+    pub members: Vec<MemberStats>,
+}
+
+/// 单个成员的统计，身份按 .mailmap 归并后的邮箱区分
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MemberStats {
+    pub name: String,
+    pub email: String,
+    pub commit_count: usize,
+    pub lines_added: usize,
+    pub lines_deleted: usize,
+    pub lines_churned: usize,
+    pub component_summary: Vec<ComponentSummary>,
+    pub tickets: Vec<String>,
+    pub sample_commits: Vec<GitCommit>,
+}
+
+#[derive(Default)]
+struct MemberAccumulator {
+    name: String,
+    email: String,
+    commit_count: usize,
+    lines_added: usize,
+    lines_deleted: usize,
+    components: HashMap<String, (usize, BTreeSet<String>)>,
+    tickets: Vec<String>,
+    sample_commits: Vec<GitCommit>,
+}
+
+const DEFAULT_SAMPLE_LIMIT: usize = 10;
+
+/// 按成员拆分的提交统计，供团队周报为每位成员生成一节
+#[tauri::command]
+pub fn get_team_breakdown(
+    path: String,
+    since: String,
+    until: String,
+    authors: Vec<String>,
+    include_submodules: Vec<String>,
+    sample_limit: Option<usize>,
+) -> Result<TeamBreakdown, String> {
+    let repo_path = resolve_repo_path(&path)?;
+    validate_date(&since)?;
+    validate_date(&until)?;
+    validate_authors(&authors)?;
+    let sample_limit = sample_limit.unwrap_or(DEFAULT_SAMPLE_LIMIT);
+
+    let mut repos = vec![(path.clone(), repo_path)];
+    for sub_path in include_submodules {
+        if let Err(e) = validate_path(&sub_path) {
+            eprintln!("子模块路径无效 ({}): {}", sub_path, e);
+            continue;
+        }
+        repos.push((sub_path.clone(), sub_path));
+    }
+
+    let mut members: HashMap<String, MemberAccumulator> = HashMap::new();
+    for (i, (project_path, repo)) in repos.iter().enumerate() {
+        let result = fetch_member_log(repo, &since, &until, &authors).and_then(|mut commits| {
+            // 与提交数口径一致，项目排除自动化提交时去掉机器人、依赖升级、批量重命名等提交
+            if automation_excluded(project_path) {
+                let counted = counted_commits(repo, &since, &until, &authors)?;
+                commits.retain(|c| counted.contains(&c.hash));
+            }
+            Ok(commits)
+        });
+        let commits = match result {
+            Ok(commits) => commits,
+            Err(e) if i == 0 => return Err(e),
+            Err(e) => {
+                eprintln!("子模块日志获取失败 ({}): {}", repo, e);
+                continue;
+            }
+        };
+
+        let (matcher, prefix) = component_matcher(project_path, repo);
+        let count_co_authors = co_authors_counted(project_path);
+        // 每位成员在本仓库中最新的若干提交，(哈希, 是否以结对身份计入)
+        let mut samples: HashMap<String, Vec<(String, bool)>> = HashMap::new();
+        for commit in commits {
+            for (name, email, via_co_author) in
+                credited_members(&commit, &authors, count_co_authors)
+            {
+                let key = email.to_lowercase();
+                let member = members
+                    .entry(key.clone())
+                    .or_insert_with(|| MemberAccumulator {
+                        name,
+                        email,
+                        ..Default::default()
+                    });
+                member.commit_count += 1;
+                for ticket in extract_tickets(&commit.message) {
+                    if !member.tickets.contains(&ticket) {
+                        member.tickets.push(ticket);
+                    }
+                }
+
+                let mut commit_areas = BTreeSet::new();
+                for (file, added, deleted) in &commit.files {
+                    member.lines_added += added;
+                    member.lines_deleted += deleted;
+                    for area in matcher.resolve(file, &prefix) {
+                        let entry = member.components.entry(area.clone()).or_default();
+                        entry.1.insert(format!("{}\0{}", i, file));
+                        commit_areas.insert(area);
+                    }
+                }
+                for area in commit_areas {
+                    member.components.entry(area).or_default().0 += 1;
+                }
+
+                // 日志按时间倒序，每位成员只需读取前 sample_limit 个提交的详情
+                let picked = samples.entry(key).or_default();
+                if picked.len() < sample_limit {
+                    picked.push((commit.hash.clone(), via_co_author));
+                }
+            }
+        }
+
+        let hashes: BTreeSet<&str> = samples
+            .values()
+            .flatten()
+            .map(|(hash, _)| hash.as_str())
+            .collect();
+        let hashes: Vec<&str> = hashes.into_iter().collect();
+        match fetch_commits_by_hash(repo, &hashes) {
+            Ok(mut details) => {
+                tag_components(project_path, repo, &mut details);
+                let details: HashMap<String, GitCommit> =
+                    details.into_iter().map(|c| (c.hash.clone(), c)).collect();
+                for (key, picked) in samples {
+                    let Some(member) = members.get_mut(&key) else {
+                        continue;
+                    };
+                    for (hash, via_co_author) in picked {
+                        if let Some(commit) = details.get(&hash) {
+                            let mut commit = commit.clone();
+                            commit.via_co_author = via_co_author;
+                            member.sample_commits.push(commit);
+                        }
+                    }
+                }
+            }
+            Err(e) => eprintln!("获取抽样提交失败 ({}): {}", repo, e),
+        }
+    }
+
+    let mut members: Vec<MemberStats> = members
+        .into_values()
+        .map(|m| m.into_stats(sample_limit))
+        .collect();
+    members.sort_by(|a, b| {
+        b.commit_count
+            .cmp(&a.commit_count)
+            .then(a.name.cmp(&b.name))
+    });
+    Ok(TeamBreakdown { members })
+}
+
+impl MemberAccumulator {
+    fn into_stats(mut self, sample_limit: usize) -> MemberStats {
+        let mut component_summary: Vec<ComponentSummary> = self
+            .components
+            .into_iter()
+            .map(|(name, (commit_count, files))| ComponentSummary {
+                name,
+                commit_count,
+                file_count: files.len(),
+            })
+            .collect();
+        component_summary.sort_by(|a, b| {
+            b.commit_count
+                .cmp(&a.commit_count)
+                .then(a.name.cmp(&b.name))
+        });
+
+        // 主仓库与子模块的提交混在一起，按时间重新排序后截取
+        self.sample_commits.sort_by(|a, b| b.date.cmp(&a.date));
+        self.sample_commits.truncate(sample_limit);
+
+        MemberStats {
+            name: self.name,
+            email: self.email,
+            commit_count: self.commit_count,
+            lines_added: self.lines_added,
+            lines_deleted: self.lines_deleted,
+            lines_churned: self.lines_added + self.lines_deleted,
+            component_summary,
+            tickets: self.tickets,
+            sample_commits: self.sample_commits,
+        }
+    }
+}
+
+struct MemberCommit {
+    hash: String,
+    name: String,
+    email: String,
+    co_authors: Vec<String>, // Co-authored-by 尾注，格式同 "Name <email>"
+    by_author: bool,         // 作者本人匹配筛选条件，否则只因结对署名被选中
+    message: String,
+    files: Vec<(String, usize, usize)>,
+}
+
+// %aN / %aE 按 .mailmap 归并同一个人的多个署名。
+// 作者和结对署名两次查询的结果按哈希去重，先出现在作者查询中的提交记为作者本人的提交
+fn fetch_member_log(
+    path: &str,
+    since: &str,
+    until: &str,
+    authors: &[String],
+) -> Result<Vec<MemberCommit>, String> {
+    let mut commits = Vec::new();
+    let mut seen = HashSet::new();
+    for (pass, filter) in author_filters(path, authors).into_iter().enumerate() {
+        let mut args = vec![
+            "log".to_string(),
+            format!("--since={}", since),
+            format!("--until={}", until),
+            "--pretty=format:%H%x00%aN%x00%aE%x00%(trailers:key=Co-authored-by,valueonly,separator=%x1f)%x00%B%x00"
+                .to_string(),
+            "--numstat".to_string(),
+            "-z".to_string(),
+            "--no-renames".to_string(),
+            "--no-merges".to_string(),
+        ];
+        args.extend(filter);
+        args.extend(revision_args(path));
+
+        let output = git_command()
+            .args(&args)
+            .current_dir(path)
+            .output()
+            .map_err(|e| format!("获取 Git 变更统计失败: {}", e))?;
+        if !output.status.success() {
+            return Err(classify_failure("Git log 执行失败", path, &output.stderr).into());
+        }
+
+        for (fields, entries) in parse_fields_numstat_z(&output.stdout, 5) {
+            if !seen.insert(fields[0].clone()) {
+                continue;
+            }
+            commits.push(MemberCommit {
+                hash: fields[0].clone(),
+                name: fields[1].clone(),
+                email: fields[2].clone(),
+                co_authors: fields[3]
+                    .split('\x1f')
+                    .map(|c| c.trim().to_string())
+                    .filter(|c| !c.is_empty())
+                    .collect(),
+                by_author: pass == 0,
+                message: fields[4].clone(),
+                // 二进制文件的行数为 "-"，按 0 处理
+                files: entries
+                    .into_iter()
+                    .map(|e| (e.path, e.added.unwrap_or(0), e.deleted.unwrap_or(0)))
+                    .collect(),
+            });
+        }
+    }
+    Ok(commits)
+}
+
+/// 提交计入的成员 (名字, 邮箱, 是否以结对身份计入)。项目开启结对署名时，
+/// Co-authored-by 中的作者也各计一次；按作者筛选时只计入与筛选条件匹配的结对作者
+fn credited_members(
+    commit: &MemberCommit,
+    authors: &[String],
+    count_co_authors: bool,
+) -> Vec<(String, String, bool)> {
+    let mut credited = Vec::new();
+    if commit.by_author {
+        credited.push((commit.name.clone(), commit.email.clone(), false));
+    }
+    if !count_co_authors {
+        return credited;
+    }
+    for co_author in &commit.co_authors {
+        let lower = co_author.to_lowercase();
+        if !authors.is_empty() && !authors.iter().any(|a| lower.contains(&a.to_lowercase())) {
+            continue;
+        }
+        let (name, email) = match co_author.split_once('<') {
+            Some((name, rest)) => (name.trim(), rest.trim_end_matches('>').trim()),
+            None => (co_author.as_str(), co_author.as_str()),
+        };
+        if credited
+            .iter()
+            .all(|(_, e, _)| !e.eq_ignore_ascii_case(email))
+        {
+            credited.push((name.to_string(), email.to_string(), true));
+        }
+    }
+    credited
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::test_util::{repo_with_automated_commits, TempRepo};
+
+    #[test]
+    fn excludes_automated_commits_from_members() {
+        let repo = repo_with_automated_commits("team-automation");
+        let breakdown = get_team_breakdown(
+            repo.path(),
+            "2000-01-01".to_string(),
+            "2099-12-31".to_string(),
+            vec![],
+            vec![],
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(breakdown.members.len(), 1);
+        let member = &breakdown.members[0];
+        assert_eq!(member.email, "dev@example.com");
+        assert_eq!(member.commit_count, 1);
+        assert_eq!(member.sample_commits.len(), 1);
+    }
+
+    fn breakdown(repo: &TempRepo, authors: &[&str]) -> TeamBreakdown {
+        get_team_breakdown(
+            repo.path(),
+            "2000-01-01".to_string(),
+            "2099-12-31".to_string(),
+            authors.iter().map(|a| a.to_string()).collect(),
+            vec![],
+            None,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn credits_co_authors_and_keeps_non_ascii_paths() {
+        let repo = TempRepo::new("team-co-author");
+        repo.write("CODEOWNERS", "/订单/ @orders\n");
+        repo.write("订单/服务.rs", "fn a() {}\n");
+        repo.commit("add order service");
+        repo.write("订单/服务.rs", "fn a() {}\nfn b() {}\n");
+        repo.commit_as(
+            "pair on order service\n\nCo-authored-by: dev <dev@example.com>",
+            "pair <pair@example.com>",
+        );
+
+        let selected = breakdown(&repo, &["dev"]);
+        assert_eq!(selected.members.len(), 1);
+        let dev = &selected.members[0];
+        assert_eq!(dev.email, "dev@example.com");
+        assert_eq!(dev.commit_count, 2);
+        assert_eq!(dev.lines_added, 3);
+        assert_eq!(dev.component_summary[0].name, "orders");
+        assert_eq!(dev.component_summary[0].commit_count, 2);
+        let paired: Vec<&GitCommit> = dev
+            .sample_commits
+            .iter()
+            .filter(|c| c.via_co_author)
+            .collect();
+        assert_eq!(paired.len(), 1);
+        assert_eq!(paired[0].author, "pair");
+        assert_eq!(paired[0].files[0].path, "订单/服务.rs");
+
+        let everyone = breakdown(&repo, &[]);
+        let counts: Vec<(&str, usize)> = everyone
+            .members
+            .iter()
+            .map(|m| (m.email.as_str(), m.commit_count))
+            .collect();
+        assert_eq!(counts, [("dev@example.com", 2), ("pair@example.com", 1)]);
+    }
+}
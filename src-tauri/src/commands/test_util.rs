@@ -0,0 +1,110 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[cfg(windows)]
+const NULL_CONFIG: &str = "NUL";
+#[cfg(not(windows))]
+const NULL_CONFIG: &str = "/dev/null";
+
+/// 测试用的临时 git 仓库，离开作用域时删除
+pub(crate) struct TempRepo {
+    dir: PathBuf,
+}
+
+impl TempRepo {
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("dev-report-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let repo = Self { dir };
+        repo.git(&["init", "-q"]);
+        repo
+    }
+
+    pub fn path(&self) -> String {
+        self.dir.to_string_lossy().to_string()
+    }
+
+    // 不读取开发者的全局和系统配置，避免 commit.gpgsign、init.templateDir 等影响夹具
+    fn command(&self) -> Command {
+        let mut cmd = Command::new("git");
+        cmd.env("GIT_CONFIG_GLOBAL", NULL_CONFIG)
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .args(["-c", "user.name=dev", "-c", "user.email=dev@example.com"])
+            .current_dir(&self.dir);
+        cmd
+    }
+
+    pub fn git(&self, args: &[&str]) {
+        let status = self.command().args(args).status().unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    pub fn output(&self, args: &[&str]) -> Vec<u8> {
+        let output = self.command().args(args).output().unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        output.stdout
+    }
+
+    /// rev-list 的结果，按时间倒序
+    pub fn rev_list(&self, range: &str) -> Vec<String> {
+        String::from_utf8(self.output(&["rev-list", range]))
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    pub fn write(&self, file: &str, content: impl AsRef<[u8]>) {
+        let path = self.dir.join(file);
+        if let Some(parent) = Path::new(&path).parent() {
+            std::fs::create_dir_all(parent).unwrap();
+        }
+        std::fs::write(path, content).unwrap();
+    }
+
+    /// 暂存全部改动并以默认身份 dev <dev@example.com> 提交
+    pub fn commit(&self, message: &str) {
+        self.git(&["add", "-A"]);
+        self.git(&["commit", "-q", "-m", message]);
+    }
+
+    /// 暂存全部改动并以指定作者提交
+    pub fn commit_as(&self, message: &str, author: &str) {
+        self.git(&["add", "-A"]);
+        self.git(&["commit", "-q", "-m", message, "--author", author]);
+    }
+
+    /// 暂存全部改动并以指定的作者时间提交，date 为 git 可识别的本地时间
+    pub fn commit_at(&self, message: &str, date: &str) {
+        self.git(&["add", "-A"]);
+        self.git(&["commit", "-q", "-m", message, "--date", date]);
+    }
+}
+
+/// 一个人工提交、一个 dependabot 提交和一个人工批量重命名提交，
+/// 默认配置下后两者都应作为自动化提交排除
+pub(crate) fn repo_with_automated_commits(name: &str) -> TempRepo {
+    let repo = TempRepo::new(name);
+    repo.write("src/main.rs", "fn main() {}\n");
+    for i in 0..10 {
+        repo.write(&format!("old/f{}.txt", i), format!("file {}\n", i));
+    }
+    repo.commit("add sources");
+
+    repo.write("Cargo.lock", "serde 1.1\n");
+    repo.commit_as(
+        "Bump serde from 1.0 to 1.1",
+        "dependabot[bot] <support@github.com>",
+    );
+
+    repo.git(&["mv", "old", "new"]);
+    repo.commit("move files");
+    repo
+}
+
+impl Drop for TempRepo {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
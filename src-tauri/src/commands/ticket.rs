@@ -0,0 +1,72 @@
+/// 形似工单号但实际是标准或编码名称的前缀，如 UTF-8、ISO-8601
+const NON_TICKET_PREFIXES: [&str; 8] = ["UTF", "ISO", "SHA", "RFC", "CVE", "GB", "MD", "TLS"];
+
+/// 提取提交说明中引用的工单号：Jira 风格的 `ABC-123` 与 GitHub/GitLab 风格的 `#123`，
+/// 按首次出现的顺序去重
+pub(crate) fn extract_tickets(message: &str) -> Vec<String> {
+    let chars: Vec<char> = message.chars().collect();
+    let mut tickets: Vec<String> = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let at_boundary = i == 0 || !is_word_char(chars[i - 1]);
+        let found = if !at_boundary {
+            None
+        } else if chars[i] == '#' {
+            issue_ref(&chars, i)
+        } else if chars[i].is_ascii_uppercase() {
+            jira_key(&chars, i)
+        } else {
+            None
+        };
+
+        match found {
+            Some((ticket, end)) => {
+                if !tickets.contains(&ticket) {
+                    tickets.push(ticket);
+                }
+                i = end;
+            }
+            None => i += 1,
+        }
+    }
+    tickets
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// 连续数字的结束位置，长度须在 1..=7 之间且后面不能紧跟字母数字
+fn number_end(chars: &[char], start: usize) -> Option<usize> {
+    let end = start
+        + chars[start..]
+            .iter()
+            .take_while(|c| c.is_ascii_digit())
+            .count();
+    let len = end - start;
+    let bounded = chars.get(end).is_none_or(|&c| !is_word_char(c));
+    ((1..=7).contains(&len) && bounded).then_some(end)
+}
+
+fn issue_ref(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let end = number_end(chars, start + 1)?;
+    Some((chars[start..end].iter().collect(), end))
+}
+
+fn jira_key(chars: &[char], start: usize) -> Option<(String, usize)> {
+    let key_end = start
+        + chars[start..]
+            .iter()
+            .take_while(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+            .count();
+    let key: String = chars[start..key_end].iter().collect();
+    if !(2..=10).contains(&key.len()) || chars.get(key_end) != Some(&'-') {
+        return None;
+    }
+    if NON_TICKET_PREFIXES.contains(&key.as_str()) {
+        return None;
+    }
+    let end = number_end(chars, key_end + 1)?;
+    Some((chars[start..end].iter().collect(), end))
+}
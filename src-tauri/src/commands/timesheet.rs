@@ -0,0 +1,318 @@
+use super::config::{load_config, GlobalConfig, TimesheetColumn, TimesheetConfig};
+use super::git::validate_date;
+use super::sessions::{build_sessions, collect_project_commits, minutes_to_hours};
+use chrono::format::{Fixed, Item, Numeric, StrftimeItems};
+use chrono::NaiveDate;
+use rust_xlsxwriter::{Format, Workbook};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TimesheetRow {
+    pub date: String,
+    pub project: String,
+    pub hours: f64,
+    pub commit_count: usize,
+    pub summary: String,
+}
+
+#[derive(Default)]
+struct DayProjectEntry {
+    minutes: i64,
+    subjects: Vec<String>,
+}
+
+#[tauri::command]
+pub fn get_timesheet_rows(since: String, until: String) -> Result<Vec<TimesheetRow>, String> {
+    validate_date(&since)?;
+    validate_date(&until)?;
+
+    let config = load_config()?;
+    build_rows(&config, &since, &until)
+}
+
+#[tauri::command]
+pub fn export_timesheet(
+    since: String,
+    until: String,
+    format: String,
+    output_path: String,
+) -> Result<usize, String> {
+    validate_date(&since)?;
+    validate_date(&until)?;
+
+    let output = Path::new(&output_path);
+    if !output.is_absolute() {
+        return Err("导出路径必须是绝对路径".to_string());
+    }
+
+    let config = load_config()?;
+    let sheet = &config.timesheet;
+    validate_date_format(&sheet.date_format)?;
+    if sheet.columns.is_empty() {
+        return Err("工时表至少需要配置一列".to_string());
+    }
+
+    let rows = build_rows(&config, &since, &until)?;
+    let table: Vec<Vec<CellValue>> = rows
+        .iter()
+        .map(|row| {
+            sheet
+                .columns
+                .iter()
+                .map(|col| cell_value(row, col, sheet))
+                .collect()
+        })
+        .collect::<Result<_, String>>()?;
+
+    match format.as_str() {
+        "csv" => write_csv(output, &sheet.columns, &table)?,
+        "xlsx" => write_xlsx(output, &sheet.columns, &table)?,
+        other => return Err(format!("不支持的导出格式: {}", other)),
+    }
+
+    eprintln!("已导出工时表: {} ({} 行)", output_path, rows.len());
+    Ok(rows.len())
+}
+
+fn build_rows(
+    config: &GlobalConfig,
+    since: &str,
+    until: &str,
+) -> Result<Vec<TimesheetRow>, String> {
+    let sessions = &config.work_sessions;
+    if sessions.gap_minutes == 0 {
+        return Err("会话间隔阈值必须大于 0".to_string());
+    }
+
+    let commits = collect_project_commits(&config.projects, since, until);
+    let spans = build_sessions(commits, sessions.gap_minutes, sessions.lead_in_minutes);
+
+    // 工时和提交摘要都按会话首个提交那天归档，保证同一行的工时与摘要对应
+    let mut entries: BTreeMap<(String, String), DayProjectEntry> = BTreeMap::new();
+    for span in spans {
+        let day = span.day();
+        for (project, minutes) in span.minutes {
+            entries.entry((day.clone(), project)).or_default().minutes += minutes;
+        }
+        for (project, subject) in span.subjects {
+            entries
+                .entry((day.clone(), project))
+                .or_default()
+                .subjects
+                .push(subject);
+        }
+    }
+
+    Ok(entries
+        .into_iter()
+        .map(|((date, project), entry)| TimesheetRow {
+            date,
+            project,
+            hours: minutes_to_hours(entry.minutes),
+            commit_count: entry.subjects.len(),
+            summary: summarize_subjects(&entry.subjects, config.timesheet.summary_max_chars),
+        })
+        .collect())
+}
+
+// 去重后按提交顺序拼接，超长截断并以省略号结尾
+fn summarize_subjects(subjects: &[String], max_chars: usize) -> String {
+    let mut unique: Vec<&str> = Vec::new();
+    for subject in subjects {
+        if !subject.is_empty() && !unique.contains(&subject.as_str()) {
+            unique.push(subject);
+        }
+    }
+
+    let joined = unique.join("; ");
+    if max_chars == 0 || joined.chars().count() <= max_chars {
+        return joined;
+    }
+    let truncated: String = joined.chars().take(max_chars.saturating_sub(1)).collect();
+    format!("{}…", truncated)
+}
+
+enum CellValue {
+    Text(String),
+    Number(f64),
+}
+
+fn cell_value(
+    row: &TimesheetRow,
+    column: &TimesheetColumn,
+    sheet: &TimesheetConfig,
+) -> Result<CellValue, String> {
+    Ok(match column.field.as_str() {
+        "date" => CellValue::Text(match NaiveDate::parse_from_str(&row.date, "%Y-%m-%d") {
+            Ok(date) => format_date(date, &sheet.date_format)?,
+            Err(_) => row.date.clone(),
+        }),
+        "project" => CellValue::Text(row.project.clone()),
+        "hours" => CellValue::Number(row.hours),
+        "commit_count" => CellValue::Number(row.commit_count as f64),
+        "summary" => CellValue::Text(row.summary.clone()),
+        "constant" => CellValue::Text(column.value.clone()),
+        _ => CellValue::Text(String::new()),
+    })
+}
+
+// to_string() 在格式化失败时会 panic，改为写入 String 并返回错误
+fn format_date(date: NaiveDate, format: &str) -> Result<String, String> {
+    let mut text = String::new();
+    write!(text, "{}", date.format(format)).map_err(|_| format!("无效的日期格式: {}", format))?;
+    Ok(text)
+}
+
+// chrono 遇到非法格式或日期中不存在的字段（时分秒、时区）会在格式化时 panic，导出前先校验
+fn validate_date_format(format: &str) -> Result<(), String> {
+    if !StrftimeItems::new(format).all(|item| is_date_item(&item)) {
+        return Err(format!("无效的日期格式: {}", format));
+    }
+    Ok(())
+}
+
+fn is_date_item(item: &Item) -> bool {
+    match item {
+        Item::Literal(_) | Item::OwnedLiteral(_) | Item::Space(_) | Item::OwnedSpace(_) => true,
+        Item::Numeric(numeric, _) => matches!(
+            numeric,
+            Numeric::Year
+                | Numeric::YearDiv100
+                | Numeric::YearMod100
+                | Numeric::IsoYear
+                | Numeric::IsoYearDiv100
+                | Numeric::IsoYearMod100
+                | Numeric::Month
+                | Numeric::Day
+                | Numeric::WeekFromSun
+                | Numeric::WeekFromMon
+                | Numeric::IsoWeek
+                | Numeric::NumDaysFromSun
+                | Numeric::WeekdayFromMon
+                | Numeric::Ordinal
+        ),
+        Item::Fixed(fixed) => matches!(
+            fixed,
+            Fixed::ShortMonthName
+                | Fixed::LongMonthName
+                | Fixed::ShortWeekdayName
+                | Fixed::LongWeekdayName
+        ),
+        Item::Error => false,
+    }
+}
+
+fn write_csv(
+    output: &Path,
+    columns: &[TimesheetColumn],
+    table: &[Vec<CellValue>],
+) -> Result<(), String> {
+    let mut content = String::from("\u{feff}"); // BOM，Excel 打开中文不乱码
+    let header: Vec<String> = columns.iter().map(|c| csv_escape(&c.header)).collect();
+    content.push_str(&header.join(","));
+    content.push_str("\r\n");
+
+    for row in table {
+        let cells: Vec<String> = row
+            .iter()
+            .map(|cell| match cell {
+                CellValue::Text(text) => csv_escape(text),
+                CellValue::Number(n) => n.to_string(),
+            })
+            .collect();
+        content.push_str(&cells.join(","));
+        content.push_str("\r\n");
+    }
+
+    fs::write(output, content).map_err(|e| format!("写入工时表失败: {}", e))
+}
+
+// 提交标题来自仓库，不可信：以公式起始符开头的单元格加 ' 前缀，Excel 打开时按文本显示而不执行
+fn csv_escape(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+fn write_xlsx(
+    output: &Path,
+    columns: &[TimesheetColumn],
+    table: &[Vec<CellValue>],
+) -> Result<(), String> {
+    let mut workbook = Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    let header_format = Format::new().set_bold();
+
+    for (col, column) in columns.iter().enumerate() {
+        worksheet
+            .write_string_with_format(0, col as u16, &column.header, &header_format)
+            .map_err(|e| format!("写入工时表失败: {}", e))?;
+    }
+
+    for (row_idx, row) in table.iter().enumerate() {
+        let row_num = row_idx as u32 + 1;
+        for (col, cell) in row.iter().enumerate() {
+            let result = match cell {
+                CellValue::Text(text) => worksheet.write_string(row_num, col as u16, text),
+                CellValue::Number(n) => worksheet.write_number(row_num, col as u16, *n),
+            };
+            result.map_err(|e| format!("写入工时表失败: {}", e))?;
+        }
+    }
+
+    workbook
+        .save(output)
+        .map_err(|e| format!("保存工时表失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_date_only_formats() {
+        assert!(validate_date_format("%Y-%m-%d").is_ok());
+        assert!(validate_date_format("%Y年%m月%d日 %a").is_ok());
+    }
+
+    #[test]
+    fn rejects_time_and_zone_formats() {
+        assert!(validate_date_format("%Y-%m-%d %H:%M").is_err());
+        assert!(validate_date_format("%H").is_err());
+        assert!(validate_date_format("%z").is_err());
+        assert!(validate_date_format("%Q").is_err());
+    }
+
+    #[test]
+    fn formatting_time_fields_returns_error() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 15).unwrap();
+        assert_eq!(format_date(date, "%d/%m/%Y").unwrap(), "15/01/2024");
+        assert!(format_date(date, "%H").is_err());
+    }
+
+    #[test]
+    fn neutralizes_formula_cells_in_csv() {
+        assert_eq!(
+            csv_escape("=HYPERLINK(\"http://x\",\"y\")"),
+            "\"'=HYPERLINK(\"\"http://x\"\",\"\"y\"\")\""
+        );
+        assert_eq!(csv_escape("+cmd|' /C calc'!A0"), "'+cmd|' /C calc'!A0");
+        assert_eq!(csv_escape("-1+1"), "'-1+1");
+        assert_eq!(csv_escape("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_escape("\tx"), "'\tx");
+        assert_eq!(csv_escape("\rx"), "\"'\rx\"");
+        assert_eq!(csv_escape("修复 a=b, c"), "\"修复 a=b, c\"");
+        assert_eq!(csv_escape("plain"), "plain");
+    }
+}
@@ -0,0 +1,106 @@
+use super::git::{
+    count_commits, fetch_git_log_limited, list_git_authors, GitCommit, SignatureSummary,
+};
+use super::hg::HgBackend;
+use super::svn::{is_svn_url, SvnBackend};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum VcsKind {
+    Git,
+    Mercurial,
+    Subversion,
+}
+
+/// file:// 地址按 SVN 仓库处理；目录下有 .hg 或 .svn 且没有 .git 时按对应的系统处理，
+/// 其余（包括 bundle 和裸仓库）按 git 处理
+pub(crate) fn detect_vcs(path: &str) -> VcsKind {
+    if is_svn_url(path) {
+        return VcsKind::Subversion;
+    }
+    let root = Path::new(path);
+    if root.join(".git").exists() {
+        VcsKind::Git
+    } else if root.join(".hg").is_dir() {
+        VcsKind::Mercurial
+    } else if root.join(".svn").is_dir() {
+        VcsKind::Subversion
+    } else {
+        VcsKind::Git
+    }
+}
+
+/// 各版本控制系统共同支持的查询，提交统一表示为 GitCommit。
+/// 签名、notes、回滚配对等 git 专有的信息只在 git 实现中填充
+pub(crate) trait VcsBackend {
+    fn log(
+        &self,
+        since: &str,
+        until: &str,
+        authors: &[String],
+        limit: usize,
+    ) -> Result<Vec<GitCommit>, String>;
+
+    /// 项目未包含自动化提交时，识别出的自动化提交计入 automated 而不计入结果
+    fn count_commits(
+        &self,
+        since: &str,
+        until: &str,
+        authors: &[String],
+        automated: &mut usize,
+    ) -> Result<usize, String>;
+
+    /// 全部提交人，格式为 "Name <email>"
+    fn authors(&self) -> Result<Vec<String>, String>;
+}
+
+pub(crate) struct GitBackend {
+    path: String,
+}
+
+impl VcsBackend for GitBackend {
+    fn log(
+        &self,
+        since: &str,
+        until: &str,
+        authors: &[String],
+        limit: usize,
+    ) -> Result<Vec<GitCommit>, String> {
+        fetch_git_log_limited(&self.path, since, until, authors, limit)
+    }
+
+    fn count_commits(
+        &self,
+        since: &str,
+        until: &str,
+        authors: &[String],
+        automated: &mut usize,
+    ) -> Result<usize, String> {
+        let mut signatures = SignatureSummary::default();
+        count_commits(
+            &self.path,
+            since,
+            until,
+            authors,
+            &mut signatures,
+            automated,
+        )
+        .map(|hashes| hashes.len())
+    }
+
+    fn authors(&self) -> Result<Vec<String>, String> {
+        list_git_authors(&self.path)
+    }
+}
+
+pub(crate) fn backend_for(path: &str) -> Box<dyn VcsBackend> {
+    match detect_vcs(path) {
+        VcsKind::Git => Box::new(GitBackend {
+            path: path.to_string(),
+        }),
+        VcsKind::Mercurial => Box::new(HgBackend::new(path)),
+        VcsKind::Subversion => Box::new(SvnBackend::new(path)),
+    }
+}
@@ -14,12 +14,28 @@ pub fn run() {
         .plugin(tauri_plugin_autostart::init(tauri_plugin_autostart::MacosLauncher::LaunchAgent, Some(vec![])))
         .invoke_handler(tauri::generate_handler![
             commands::git::check_git_installed,
+            commands::git::get_git_capabilities,
             commands::git::get_git_log,
             commands::git::get_git_stats,
             commands::git::get_git_authors,
             commands::git::get_git_submodules,
             commands::git::validate_repo_path,
+            commands::git::trust_repository,
             commands::git::get_folder_name,
+            commands::repo::get_repo_identity,
+            commands::remote::get_git_remotes,
+            commands::remote::get_git_tags,
+            commands::notes::get_commit_note,
+            commands::notes::set_commit_note,
+            commands::excerpt::get_diff_excerpts,
+            commands::churn::get_churn_analysis,
+            commands::survival::get_code_survival,
+            commands::compare::get_period_comparison,
+            commands::team::get_team_breakdown,
+            commands::activity::get_activity_heatmap,
+            commands::sessions::get_work_sessions,
+            commands::timesheet::get_timesheet_rows,
+            commands::timesheet::export_timesheet,
             commands::config::load_config,
             commands::config::save_config,
             commands::config::get_config_path,
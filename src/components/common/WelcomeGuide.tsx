@@ -19,7 +19,7 @@ const STEPS = [
   {
     icon: <ProjectOutlined />,
     title: "添加 Git 项目",
-    desc: "在「项目管理」页面添加本地 Git 仓库，支持子模块识别和提交人筛选。项目名默认为文件夹名。",
+    desc: "在「项目管理」页面添加本地 Git、Mercurial 或 SVN 仓库，支持子模块识别和提交人筛选。项目名默认为文件夹名。",
   },
   {
     icon: <SettingOutlined />,
@@ -1,10 +1,10 @@
 import { memo, useState, useEffect } from 'react';
 import { Button, Input, Form, Checkbox, Space, Typography, Card, Spin, Alert } from 'antd';
-import { ArrowLeftOutlined, FolderOpenOutlined, CheckCircleFilled, LoadingOutlined } from '@ant-design/icons';
+import { ArrowLeftOutlined, FolderOpenOutlined, FileZipOutlined, CheckCircleFilled, LoadingOutlined, PlusOutlined, DeleteOutlined } from '@ant-design/icons';
 import { invoke } from '@tauri-apps/api/core';
 import { open } from '@tauri-apps/plugin-dialog';
 import { useAppStore } from '@/store/useAppStore';
-import type { ProjectConfig, SubmoduleConfig } from '@/types';
+import type { ProjectConfig, SubmoduleConfig, RepoIdentity, ComponentMapping } from '@/types';
 
 const { Title, Text } = Typography;
 
@@ -17,16 +17,24 @@ const ProjectForm = (props: ProjectFormProps) => {
   const { project, onClose } = props;
   const addProject = useAppStore(state => state.addProject);
   const updateProject = useAppStore(state => state.updateProject);
+  const projects = useAppStore(state => state.config?.projects) || [];
 
   const [name, setName] = useState(project?.name || '');
   const [nameManuallySet, setNameManuallySet] = useState(!!project?.name);
   const [repoPath, setRepoPath] = useState(project?.repo_path || '');
   const [authors, setAuthors] = useState<string[]>(project?.authors || []);
+  const [countCoAuthored, setCountCoAuthored] = useState(project?.count_co_authored ?? true);
+  const [includeAutomated, setIncludeAutomated] = useState(project?.include_automated ?? false);
+  const [components, setComponents] = useState<ComponentMapping[]>(project?.components || []);
+  const [useCodeowners, setUseCodeowners] = useState(project?.use_codeowners ?? true);
   const [availableAuthors, setAvailableAuthors] = useState<string[]>([]);
   const [submodules, setSubmodules] = useState<SubmoduleConfig[]>(project?.submodules || []);
   const [validating, setValidating] = useState(false);
   const [repoValid, setRepoValid] = useState<boolean | null>(null);
   const [authorSearch, setAuthorSearch] = useState('');
+  const [ownershipError, setOwnershipError] = useState<string | null>(null);
+  const [duplicateError, setDuplicateError] = useState<string | null>(null);
+  const [saving, setSaving] = useState(false);
 
   useEffect(() => {
     if (repoPath) validateAndLoadRepo();
@@ -34,6 +42,8 @@ const ProjectForm = (props: ProjectFormProps) => {
 
   const validateAndLoadRepo = async () => {
     setValidating(true);
+    setOwnershipError(null);
+    setDuplicateError(null);
     try {
       const valid = await invoke<boolean>('validate_repo_path', { path: repoPath });
       setRepoValid(valid);
@@ -47,10 +57,20 @@ const ProjectForm = (props: ProjectFormProps) => {
         const gitSubmodules = await invoke<{ name: string; path: string }[]>('get_git_submodules', { path: repoPath });
         if (!project) setSubmodules(gitSubmodules.map(s => ({ ...s, enabled: true })));
       }
-    } catch { setRepoValid(false); }
+    } catch (e) {
+      setRepoValid(false);
+      if (String(e).includes('safe.directory')) setOwnershipError(String(e));
+    }
     setValidating(false);
   };
 
+  const handleTrustRepo = async () => {
+    try {
+      await invoke('trust_repository', { path: repoPath });
+      await validateAndLoadRepo();
+    } catch (e) { console.error('信任仓库失败:', e); }
+  };
+
   const handleSelectFolder = async () => {
     try {
       const selected = await open({ directory: true, multiple: false });
@@ -58,19 +78,63 @@ const ProjectForm = (props: ProjectFormProps) => {
     } catch (e) { console.error('选择文件夹失败:', e); }
   };
 
+  const handleSelectBundle = async () => {
+    try {
+      const selected = await open({ directory: false, multiple: false, filters: [{ name: 'Git Bundle', extensions: ['bundle'] }] });
+      if (selected) setRepoPath(selected as string);
+    } catch (e) { console.error('选择 bundle 文件失败:', e); }
+  };
+
+  // 同一仓库的多个工作树共用 common_dir，重复添加会让提交被统计两次；Mercurial、SVN 仓库无法识别时跳过
+  const findDuplicateProject = async (path: string) => {
+    let identity: RepoIdentity;
+    try {
+      identity = await invoke<RepoIdentity>('get_repo_identity', { path });
+    } catch {
+      return null;
+    }
+    for (const other of projects) {
+      if (other.id === project?.id) continue;
+      try {
+        const otherIdentity = await invoke<RepoIdentity>('get_repo_identity', { path: other.repo_path });
+        if (otherIdentity.common_dir === identity.common_dir) return other;
+      } catch {
+        // 其他项目路径失效时不影响保存
+      }
+    }
+    return null;
+  };
+
   const handleSave = async () => {
     if (!repoPath.trim()) return;
+    setSaving(true);
+    const duplicate = await findDuplicateProject(repoPath.trim());
+    setSaving(false);
+    if (duplicate) {
+      setDuplicateError(`该仓库已作为项目「${duplicate.name}」添加（${duplicate.repo_path}），同一仓库的不同工作树会被合并统计，无需重复添加`);
+      return;
+    }
     const finalName = name.trim() || repoPath.split('/').pop() || 'Untitled';
     const data: ProjectConfig = {
+      ...project,
       id: project?.id || crypto.randomUUID(),
       name: finalName, repo_path: repoPath.trim(),
-      authors, submodules,
+      authors, submodules, count_co_authored: countCoAuthored,
+      include_automated: includeAutomated,
+      // 未填完整的映射行不保存
+      components: components
+        .map(c => ({ pattern: c.pattern.trim(), component: c.component.trim() }))
+        .filter(c => c.pattern && c.component),
+      use_codeowners: useCodeowners,
     };
     if (project) await updateProject(data);
     else await addProject(data);
     onClose();
   };
 
+  const updateComponent = (index: number, patch: Partial<ComponentMapping>) =>
+    setComponents(prev => prev.map((c, i) => (i === index ? { ...c, ...patch } : c)));
+
   const filteredAuthors = availableAuthors.filter(a => a.toLowerCase().includes(authorSearch.toLowerCase()));
 
   return (
@@ -79,7 +143,7 @@ const ProjectForm = (props: ProjectFormProps) => {
         <Button icon={<ArrowLeftOutlined />} onClick={onClose} />
         <div>
           <Title level={4} style={{ margin: 0 }}>{project ? '编辑项目' : '添加项目'}</Title>
-          <Text type="secondary">配置 Git 仓库路径和提交人筛选</Text>
+          <Text type="secondary">配置 Git、Mercurial 或 SVN 仓库路径和提交人筛选</Text>
         </div>
       </Space>
 
@@ -88,7 +152,7 @@ const ProjectForm = (props: ProjectFormProps) => {
           <Form layout="vertical">
             <Form.Item label="仓库路径" required
               validateStatus={repoValid === false ? 'error' : repoValid === true ? 'success' : undefined}
-              help={repoValid === false ? '路径无效或不是 Git 仓库' : undefined}>
+              help={repoValid === false ? '路径无效，需为 Git 仓库、Mercurial 仓库、SVN 工作副本或 file:// 仓库地址、裸仓库或 .bundle 文件' : undefined}>
               <Space.Compact style={{ width: '100%' }}>
                 <Input
                   value={repoPath} onChange={e => setRepoPath(e.target.value)}
@@ -96,9 +160,19 @@ const ProjectForm = (props: ProjectFormProps) => {
                   suffix={validating ? <LoadingOutlined /> : repoValid ? <CheckCircleFilled style={{ color: '#52c41a' }} /> : null}
                 />
                 <Button icon={<FolderOpenOutlined />} onClick={handleSelectFolder}>选择</Button>
+                <Button icon={<FileZipOutlined />} onClick={handleSelectBundle}>Bundle</Button>
               </Space.Compact>
             </Form.Item>
 
+            {ownershipError && (
+              <Alert type="warning" showIcon style={{ marginBottom: 16 }} message={ownershipError}
+                action={<Button size="small" onClick={handleTrustRepo}>信任此仓库</Button>} />
+            )}
+
+            {duplicateError && (
+              <Alert type="error" showIcon style={{ marginBottom: 16 }} message={duplicateError} />
+            )}
+
             <Form.Item label="项目名称（可选，默认用文件夹名）">
               <Input
                 value={name}
@@ -123,6 +197,28 @@ const ProjectForm = (props: ProjectFormProps) => {
           </Card>
         )}
 
+        <Card title="业务模块" style={{ marginTop: 12 }}>
+          <Text type="secondary" style={{ display: 'block', marginBottom: 8 }}>
+            路径使用 gitignore 风格的通配符，如 /src/order/ 或 **/*.sql；显式映射优先于 CODEOWNERS
+          </Text>
+          {components.map((c, index) => (
+            <Space.Compact key={index} style={{ width: '100%', marginBottom: 8 }}>
+              <Input value={c.pattern} placeholder="/src/order/" style={{ fontFamily: 'monospace' }}
+                onChange={e => updateComponent(index, { pattern: e.target.value })} />
+              <Input value={c.component} placeholder="订单中心"
+                onChange={e => updateComponent(index, { component: e.target.value })} />
+              <Button icon={<DeleteOutlined />} onClick={() => setComponents(prev => prev.filter((_, i) => i !== index))} />
+            </Space.Compact>
+          ))}
+          <Button type="dashed" icon={<PlusOutlined />} block
+            onClick={() => setComponents(prev => [...prev, { pattern: '', component: '' }])}>
+            添加映射
+          </Button>
+          <Checkbox checked={useCodeowners} onChange={e => setUseCodeowners(e.target.checked)} style={{ marginTop: 8 }}>
+            未命中映射的文件按 CODEOWNERS 归属模块
+          </Checkbox>
+        </Card>
+
         {availableAuthors.length > 0 && (
           <Card title="提交人筛选（不选则包含全部）" style={{ marginTop: 12 }}>
             <Input placeholder="搜索提交人..." value={authorSearch} onChange={e => setAuthorSearch(e.target.value)}
@@ -134,12 +230,19 @@ const ProjectForm = (props: ProjectFormProps) => {
                 </Space>
               </Checkbox.Group>
             </div>
+            <Checkbox checked={countCoAuthored} onChange={e => setCountCoAuthored(e.target.checked)} style={{ marginTop: 8 }}>
+              同时统计 Co-authored-by 中包含所选提交人的结对提交
+            </Checkbox>
+            <br />
+            <Checkbox checked={includeAutomated} onChange={e => setIncludeAutomated(e.target.checked)} style={{ marginTop: 8 }}>
+              统计机器人、依赖升级、版本发布、代码格式化等自动化提交
+            </Checkbox>
           </Card>
         )}
 
         <div style={{ marginTop: 16, display: 'flex', justifyContent: 'flex-end', gap: 8 }}>
           <Button onClick={onClose}>取消</Button>
-          <Button type="primary" onClick={handleSave} disabled={!repoPath.trim()}>
+          <Button type="primary" onClick={handleSave} loading={saving} disabled={!repoPath.trim()}>
             {project ? '保存修改' : '添加项目'}
           </Button>
         </div>
@@ -1,17 +1,17 @@
 import { memo, useState, useEffect, useCallback, useRef } from 'react';
-import { Button, Select, Card, Checkbox, InputNumber, Typography, Space, List, Tag, Spin, Empty, App, Row, Col, Statistic, Collapse, DatePicker } from 'antd';
-import { SyncOutlined, CopyOutlined, ExportOutlined, PlayCircleOutlined, LoadingOutlined, FileTextOutlined, TeamOutlined, CodeOutlined } from '@ant-design/icons';
+import { Button, Select, Card, Checkbox, InputNumber, Typography, Space, List, Tag, Spin, Empty, App, Row, Col, Statistic, Collapse, DatePicker, Modal, Input } from 'antd';
+import { SyncOutlined, CopyOutlined, ExportOutlined, TableOutlined, PlayCircleOutlined, LoadingOutlined, FileTextOutlined, TeamOutlined, CodeOutlined, EditOutlined } from '@ant-design/icons';
 import { invoke } from '@tauri-apps/api/core';
 import { listen } from '@tauri-apps/api/event';
 import { writeText } from '@tauri-apps/plugin-clipboard-manager';
 import { save } from '@tauri-apps/plugin-dialog';
 import { writeTextFile } from '@tauri-apps/plugin-fs';
 import { useAppStore } from '@/store/useAppStore';
-import { getTimeRangeByType, formatDateShort } from '@/utils/time';
-import { buildPromptFromStats } from '@/utils/prompt';
+import { getTimeRangeByType, getPreviousTimeRangeByType, getPreviousCustomRange, formatDateShort } from '@/utils/time';
+import { buildPromptFromStats, formatSize } from '@/utils/prompt';
 import ReactMarkdown from 'react-markdown';
 import { REPORT_TYPE_LABELS } from '@/types';
-import type { ReportType, GitStats, StreamChunk } from '@/types';
+import type { ReportType, TimesheetFormat, GitStats, GitCommit, StreamChunk, DiffExcerptReport, SurvivalReport, PeriodComparison, TeamBreakdown, MemberStats } from '@/types';
 import dayjs, { Dayjs } from 'dayjs';
 
 const { Title, Text } = Typography;
@@ -26,15 +26,21 @@ const ReportPage = () => {
   const [useCustomRange, setUseCustomRange] = useState(false);
   const [customDateRange, setCustomDateRange] = useState<[Dayjs, Dayjs] | null>(null);
   const [crossDay, setCrossDay] = useState(false);
+  const [teamMode, setTeamMode] = useState(false);
   const [wordCount, setWordCount] = useState(100);
 
   const [stats, setStats] = useState<GitStats | null>(null);
   const [loadingStats, setLoadingStats] = useState(false);
   const [generatedContent, setGeneratedContent] = useState('');
   const [isStreaming, setIsStreaming] = useState(false);
+  const [noteCommit, setNoteCommit] = useState<GitCommit | null>(null);
+  const [noteDraft, setNoteDraft] = useState('');
+  const [savingNote, setSavingNote] = useState(false);
 
   const contentRef = useRef<HTMLDivElement>(null);
   const streamContentRef = useRef('');
+  // 抽样提交哈希 -> 所属仓库路径，多项目合并后用于写入备注；子模块中的提交指向子模块路径
+  const commitReposRef = useRef(new Map<string, string>());
 
   const projects = config?.projects || [];
 
@@ -78,6 +84,7 @@ const ReportPage = () => {
     }
     setLoadingStats(true);
     setStats(null);
+    commitReposRef.current.clear();
     
     try {
       const { since, until } = getTimeRange();
@@ -89,6 +96,7 @@ const ReportPage = () => {
           path: proj.repo_path, since, until,
           authors: proj.authors, includeSubmodules: enabledSubs,
         });
+        result.sample_commits.forEach(c => commitReposRef.current.set(c.hash, c.repo || proj.repo_path));
         setStats(result);
       } else {
         let mergedStats: GitStats = {
@@ -98,6 +106,14 @@ const ReportPage = () => {
           date_range: [since, until],
           sample_commits: [],
           file_changes_summary: [],
+          component_summary: [],
+          signature_summary: { signed: 0, verified: 0, unsigned: 0 },
+          revert_summary: { revert_count: 0, reverted_count: 0, pairs: [] },
+          automated_count: 0,
+          outlier_count: 0,
+          pull_requests: [],
+          pull_requests_truncated: false,
+          asset_summary: { file_count: 0, total_size: 0, updates: [] },
         };
 
         for (const proj of selectedProjects) {
@@ -111,6 +127,29 @@ const ReportPage = () => {
             mergedStats.total_files_changed += result.total_files_changed;
             mergedStats.authors = [...new Set([...mergedStats.authors, ...result.authors])];
             mergedStats.sample_commits = [...mergedStats.sample_commits, ...result.sample_commits];
+            result.sample_commits.forEach(c => commitReposRef.current.set(c.hash, c.repo || proj.repo_path));
+            mergedStats.signature_summary.signed += result.signature_summary.signed;
+            mergedStats.signature_summary.verified += result.signature_summary.verified;
+            mergedStats.signature_summary.unsigned += result.signature_summary.unsigned;
+            mergedStats.revert_summary.revert_count += result.revert_summary.revert_count;
+            mergedStats.revert_summary.reverted_count += result.revert_summary.reverted_count;
+            mergedStats.revert_summary.pairs.push(...result.revert_summary.pairs);
+            mergedStats.automated_count += result.automated_count;
+            mergedStats.outlier_count += result.outlier_count;
+            mergedStats.pull_requests.push(...result.pull_requests);
+            mergedStats.pull_requests_truncated ||= result.pull_requests_truncated;
+            mergedStats.asset_summary.file_count += result.asset_summary.file_count;
+            mergedStats.asset_summary.total_size += result.asset_summary.total_size;
+            mergedStats.asset_summary.updates.push(...result.asset_summary.updates);
+            result.component_summary.forEach(c => {
+              const existing = mergedStats.component_summary.find(m => m.name === c.name);
+              if (existing) {
+                existing.commit_count += c.commit_count;
+                existing.file_count += c.file_count;
+              } else {
+                mergedStats.component_summary.push({ ...c });
+              }
+            });
           } catch (projError) {
             console.error(`获取项目 ${proj.name} 统计失败:`, projError);
             message.error(`获取项目 ${proj.name} 统计失败: ${projError}`);
@@ -119,6 +158,9 @@ const ReportPage = () => {
 
         mergedStats.sample_commits.sort((a, b) => b.date.localeCompare(a.date));
         mergedStats.sample_commits = mergedStats.sample_commits.slice(0, 50);
+        mergedStats.component_summary.sort((a, b) => b.commit_count - a.commit_count);
+        mergedStats.asset_summary.updates.sort((a, b) => b.change_count - a.change_count);
+        mergedStats.asset_summary.updates = mergedStats.asset_summary.updates.slice(0, 20);
         setStats(mergedStats);
       }
       
@@ -162,7 +204,145 @@ const ReportPage = () => {
     const projectNames = selectedProjects.map(p => p.name).join(' + ');
     const { since, until } = getTimeRange();
     const timeRangeText = useCustomRange ? `${since} 至 ${until}` : `${REPORT_TYPE_LABELS[reportType]}（${since} 至 ${until}）`;
-    const prompt = buildPromptFromStats(stats, reportType, wordCount, projectNames, timeRangeText);
+
+    // 预算按项目平分，单个项目失败不影响生成
+    const excerptBudget = Math.floor((config.diff_excerpts?.token_budget ?? 1500) / selectedProjects.length);
+    const excerptTexts: string[] = [];
+    if (excerptBudget > 0) {
+      for (const proj of selectedProjects) {
+        try {
+          const enabledSubs = proj.submodules.filter(s => s.enabled).map(s => s.path);
+          const result = await invoke<DiffExcerptReport>('get_diff_excerpts', {
+            path: proj.repo_path, since, until,
+            authors: proj.authors, includeSubmodules: enabledSubs,
+            tokenBudget: excerptBudget,
+          });
+          if (result.text) {
+            excerptTexts.push(selectedProjects.length > 1 ? `【${proj.name}】\n${result.text}` : result.text);
+          }
+        } catch (excerptError) {
+          console.error(`获取项目 ${proj.name} 代码片段失败:`, excerptError);
+        }
+      }
+    }
+
+    // 季报、年报的时间跨度足够长，代码留存情况才有参考意义
+    const survivalTexts: string[] = [];
+    if (reportType === 'quarterly' || reportType === 'yearly') {
+      const percent = (rate: number) => `${Math.round(rate * 100)}%`;
+      for (const proj of selectedProjects) {
+        try {
+          const enabledSubs = proj.submodules.filter(s => s.enabled).map(s => s.path);
+          const result = await invoke<SurvivalReport>('get_code_survival', {
+            path: proj.repo_path, since, until,
+            authors: proj.authors, includeSubmodules: enabledSubs,
+          });
+          if (result.lines_added === 0) continue;
+          const components = result.components
+            .slice(0, 5)
+            .map(c => `${c.name} ${c.lines_surviving}/${c.lines_added} 行（${percent(c.survival_rate)}）`)
+            .join('，');
+          survivalTexts.push(
+            `- ${proj.name}：新增 ${result.lines_added} 行，至今保留 ${result.lines_surviving} 行（${percent(result.survival_rate)}）`
+            + (components ? `；按模块：${components}` : '')
+          );
+        } catch (survivalError) {
+          console.error(`获取项目 ${proj.name} 代码留存失败:`, survivalError);
+        }
+      }
+    }
+
+    // 与上一周期对比，让报告能描述趋势而不只是给出数字
+    const trendTexts: string[] = [];
+    if (reportType !== 'daily' || useCustomRange) {
+      const previous = useCustomRange ? getPreviousCustomRange(since, until) : getPreviousTimeRangeByType(reportType, since, until);
+      const signed = (n: number) => (n > 0 ? `+${n}` : `${n}`);
+      const rate = (r?: number | null) => (r == null ? '' : `，${r >= 0 ? '+' : ''}${Math.round(r * 100)}%`);
+      for (const proj of selectedProjects) {
+        try {
+          const enabledSubs = proj.submodules.filter(s => s.enabled).map(s => s.path);
+          const { current, previous: prev, delta } = await invoke<PeriodComparison>('get_period_comparison', {
+            path: proj.repo_path, since, until,
+            previousSince: previous.since, previousUntil: previous.until,
+            authors: proj.authors, includeSubmodules: enabledSubs,
+          });
+          const parts = [
+            `提交 ${current.commit_count} 条（上期 ${prev.commit_count}，${signed(delta.commit_count)}${rate(delta.commit_change_rate)}）`,
+            `改动 ${current.lines_churned} 行（上期 ${prev.lines_churned}${rate(delta.churn_change_rate)}）`,
+            `关联工单 ${current.tickets.length} 个（上期 ${prev.tickets.length}）`,
+          ];
+          if (current.components.length > 0 || prev.components.length > 0) {
+            parts.push(`涉及模块 ${current.components.length} 个（上期 ${prev.components.length}）`);
+          }
+          if (delta.new_components.length > 0) {
+            parts.push(`新涉及模块：${delta.new_components.join('、')}`);
+          }
+          trendTexts.push(`- ${proj.name}：${parts.join('；')}`);
+        } catch (compareError) {
+          console.error(`获取项目 ${proj.name} 环比数据失败:`, compareError);
+        }
+      }
+    }
+
+    // 团队报告按成员汇总，多个项目中的同一成员按邮箱合并
+    const memberTexts: string[] = [];
+    if (teamMode) {
+      const members = new Map<string, MemberStats>();
+      for (const proj of selectedProjects) {
+        try {
+          const enabledSubs = proj.submodules.filter(s => s.enabled).map(s => s.path);
+          const result = await invoke<TeamBreakdown>('get_team_breakdown', {
+            path: proj.repo_path, since, until,
+            authors: proj.authors, includeSubmodules: enabledSubs,
+            sampleLimit: 8,
+          });
+          result.members.forEach(m => {
+            const key = m.email.toLowerCase();
+            const existing = members.get(key);
+            if (!existing) {
+              members.set(key, { ...m, component_summary: [...m.component_summary], tickets: [...m.tickets] });
+              return;
+            }
+            existing.commit_count += m.commit_count;
+            existing.lines_added += m.lines_added;
+            existing.lines_deleted += m.lines_deleted;
+            existing.lines_churned += m.lines_churned;
+            existing.tickets = [...new Set([...existing.tickets, ...m.tickets])];
+            existing.sample_commits = [...existing.sample_commits, ...m.sample_commits];
+            m.component_summary.forEach(c => {
+              const component = existing.component_summary.find(e => e.name === c.name);
+              if (component) {
+                component.commit_count += c.commit_count;
+                component.file_count += c.file_count;
+              } else {
+                existing.component_summary.push({ ...c });
+              }
+            });
+          });
+        } catch (teamError) {
+          console.error(`获取项目 ${proj.name} 成员统计失败:`, teamError);
+        }
+      }
+      [...members.values()]
+        .sort((a, b) => b.commit_count - a.commit_count)
+        .forEach(m => {
+          const lines = [`### ${m.name}：提交 ${m.commit_count} 条，改动 ${m.lines_churned} 行`];
+          if (m.component_summary.length > 0) {
+            lines.push(`模块：${m.component_summary.slice(0, 5).map(c => c.name).join('、')}`);
+          }
+          if (m.tickets.length > 0) {
+            lines.push(`工单：${m.tickets.slice(0, 10).join('、')}`);
+          }
+          lines.push(...m.sample_commits.slice(0, 8).map(c => `- ${c.message}`));
+          memberTexts.push(lines.join('\n'));
+        });
+    }
+
+    const prompt = buildPromptFromStats(
+      stats, reportType, wordCount, projectNames, timeRangeText,
+      excerptTexts.join('\n'), survivalTexts.join('\n'), trendTexts.join('\n'),
+      memberTexts.join('\n\n')
+    );
 
     let unlistenFn: (() => void) | null = null;
     const unlisten = await listen<StreamChunk>('llm-stream', (event) => {
@@ -218,10 +398,70 @@ const ReportPage = () => {
         defaultPath: `${names}-${reportType}-${new Date().toISOString().split('T')[0]}.md`,
         filters: [{ name: 'Markdown', extensions: ['md'] }],
       });
-      if (filePath) { await writeTextFile(filePath, generatedContent); message.success('导出成功'); }
+      // 附上抽样提交的网页链接，便于从报告跳转到具体改动
+      const linked = (stats?.sample_commits ?? []).filter(c => c.web_url).slice(0, 20);
+      const appendix = linked.length > 0
+        ? `\n\n## 相关提交\n\n${linked.map(c => `- [${c.hash.slice(0, 7)}](${c.web_url}) ${c.message}`).join('\n')}\n`
+        : '';
+      if (filePath) { await writeTextFile(filePath, generatedContent + appendix); message.success('导出成功'); }
     } catch (e) { console.error('导出失败:', e); }
   };
 
+  // 工时表汇总所有已配置项目，列和日期格式取自配置文件的 timesheet 项，按所选文件扩展名决定格式
+  const handleExportTimesheet = async () => {
+    try {
+      const { since, until } = getTimeRange();
+      const filePath = await save({
+        defaultPath: `工时表-${since.slice(0, 10)}-${until.slice(0, 10)}.xlsx`,
+        filters: [{ name: 'Excel', extensions: ['xlsx'] }, { name: 'CSV', extensions: ['csv'] }],
+      });
+      if (!filePath) return;
+      const format: TimesheetFormat = filePath.toLowerCase().endsWith('.csv') ? 'csv' : 'xlsx';
+      const count = await invoke<number>('export_timesheet', { since, until, format, outputPath: filePath });
+      message.success(`已导出 ${count} 行工时记录`);
+    } catch (e) {
+      console.error('导出工时表失败:', e);
+      message.error(`导出工时表失败: ${e}`);
+    }
+  };
+
+  // 打开时重新读取备注，避免在其他地方修改后覆盖
+  const handleOpenNote = async (commit: GitCommit) => {
+    const path = commitReposRef.current.get(commit.hash);
+    if (!path) return;
+    setNoteCommit(commit);
+    setNoteDraft(commit.note ?? '');
+    try {
+      const note = await invoke<string | null>('get_commit_note', { path, hash: commit.hash });
+      setNoteDraft(note ?? '');
+    } catch (e) {
+      console.error('读取备注失败:', e);
+    }
+  };
+
+  // 备注写入 git notes，清空内容即删除
+  const handleSaveNote = async () => {
+    if (!noteCommit) return;
+    const path = commitReposRef.current.get(noteCommit.hash);
+    if (!path) return;
+    setSavingNote(true);
+    try {
+      await invoke('set_commit_note', { path, hash: noteCommit.hash, note: noteDraft });
+      const note = noteDraft.trim() || null;
+      setStats(prev => prev && {
+        ...prev,
+        sample_commits: prev.sample_commits.map(c => (c.hash === noteCommit.hash ? { ...c, note } : c)),
+      });
+      setNoteCommit(null);
+      message.success(note ? '备注已保存' : '备注已删除');
+    } catch (e) {
+      console.error('保存备注失败:', e);
+      message.error(`保存备注失败: ${e}`);
+    } finally {
+      setSavingNote(false);
+    }
+  };
+
   return (
     <div style={{ padding: 24, height: '100%', display: 'flex', flexDirection: 'column' }} className="animate-fadeIn">
       <div style={{ marginBottom: 16 }}>
@@ -316,6 +556,13 @@ const ReportPage = () => {
                   跨天统计（凌晨提交算前一天）
                 </Checkbox>
 
+                <Checkbox
+                  checked={teamMode}
+                  onChange={e => setTeamMode(e.target.checked)}
+                >
+                  团队报告（按成员分节）
+                </Checkbox>
+
                 <div>
                   <Text type="secondary" style={{ fontSize: 12 }}>字数限制</Text>
                   <InputNumber value={wordCount} onChange={v => setWordCount(v || 100)} min={50} max={3000} style={{ width: '100%' }} />
@@ -325,14 +572,19 @@ const ReportPage = () => {
 
             <Card size="small" title="提交统计"
               extra={
-                <Button 
-                  size="small" 
-                  icon={<SyncOutlined spin={loadingStats} />} 
-                  onClick={handleLoadStats}
-                  loading={loadingStats}
-                  disabled={selectedProjectIds.length === 0}>
-                  刷新
-                </Button>
+                <Space size={4}>
+                  <Button size="small" icon={<TableOutlined />} onClick={handleExportTimesheet}>
+                    导出工时
+                  </Button>
+                  <Button 
+                    size="small" 
+                    icon={<SyncOutlined spin={loadingStats} />} 
+                    onClick={handleLoadStats}
+                    loading={loadingStats}
+                    disabled={selectedProjectIds.length === 0}>
+                    刷新
+                  </Button>
+                </Space>
               }
               style={{ marginBottom: 12 }}>
               {loadingStats ? (
@@ -359,6 +611,11 @@ const ReportPage = () => {
                       <Statistic title="作者数" value={stats.authors.length} prefix={<TeamOutlined />} valueStyle={{ fontSize: 18 }} />
                     </Col>
                   </Row>
+                  {stats.asset_summary.file_count > 0 && (
+                    <Text type="secondary" style={{ fontSize: 12 }}>
+                      资源更新 {stats.asset_summary.file_count} 个文件（{formatSize(stats.asset_summary.total_size)}），未计入文件数
+                    </Text>
+                  )}
                   {stats.sample_commits.length > 0 && (
                     <Collapse size="small" items={[{
                       key: 'samples',
@@ -366,9 +623,10 @@ const ReportPage = () => {
                       children: (
                         <List size="small" dataSource={stats.sample_commits.slice(0, 10)}
                           renderItem={c => (
-                            <List.Item style={{ padding: '4px 0' }}>
+                            <List.Item style={{ padding: '4px 0' }}
+                              actions={[<Button key="note" type="text" size="small" icon={<EditOutlined />} onClick={() => handleOpenNote(c)} />]}>
                               <List.Item.Meta
-                                title={<Text style={{ fontSize: 12 }} ellipsis>{c.message}</Text>}
+                                title={<Text style={{ fontSize: 12 }} ellipsis={{ tooltip: c.note }}>{c.message}{c.note && <Tag color="blue" style={{ marginLeft: 6, fontSize: 10 }}>备注</Tag>}</Text>}
                                 description={<Text type="secondary" style={{ fontSize: 11 }}>{c.author} · {formatDateShort(c.date)}</Text>}
                               />
                             </List.Item>
@@ -413,6 +671,17 @@ const ReportPage = () => {
           </Card>
         </div>
       )}
+
+      <Modal title="提交备注" open={!!noteCommit} okText="保存" cancelText="取消"
+        confirmLoading={savingNote} onOk={handleSaveNote} onCancel={() => setNoteCommit(null)} destroyOnHidden>
+        {noteCommit && (
+          <Space direction="vertical" style={{ width: '100%' }}>
+            <Text type="secondary" style={{ fontSize: 12 }}>{noteCommit.hash.slice(0, 7)} {noteCommit.message}</Text>
+            <Input.TextArea rows={4} value={noteDraft} onChange={e => setNoteDraft(e.target.value)}
+              placeholder="补充提交信息中没有的背景，生成报告时一并提供给模型；留空则删除" />
+          </Space>
+        )}
+      </Modal>
     </div>
   );
 };
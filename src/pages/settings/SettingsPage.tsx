@@ -1,12 +1,20 @@
 import { memo, useState, useEffect } from 'react';
-import { Card, InputNumber, Switch, Button, Typography, Space, Descriptions, Input, App } from 'antd';
-import { SaveOutlined, UndoOutlined } from '@ant-design/icons';
+import { Card, InputNumber, Switch, Button, Typography, Space, Descriptions, Input, Select, App } from 'antd';
+import { SaveOutlined, UndoOutlined, PlusOutlined, DeleteOutlined } from '@ant-design/icons';
 import { invoke } from '@tauri-apps/api/core';
 import { useAppStore } from '@/store/useAppStore';
-import type { GlobalConfig } from '@/types';
+import type { GlobalConfig, GitHostConfig, HostKind } from '@/types';
 
 const { Title, Text } = Typography;
 
+const HOST_KIND_OPTIONS: { value: HostKind; label: string }[] = [
+  { value: 'github', label: 'GitHub' },
+  { value: 'gitlab', label: 'GitLab' },
+  { value: 'gitea', label: 'Gitea / Forgejo' },
+  { value: 'gitee', label: 'Gitee' },
+  { value: 'bitbucket', label: 'Bitbucket' },
+];
+
 const SettingsPage = () => {
   const { message } = App.useApp();
   const config = useAppStore(state => state.config);
@@ -41,7 +49,13 @@ const SettingsPage = () => {
 
   const handleSave = async () => {
     if (!localConfig) return;
-    await saveConfig(localConfig);
+    // 未填主机名的平台行不保存
+    await saveConfig({
+      ...localConfig,
+      git_hosts: (localConfig.git_hosts || [])
+        .map(h => ({ ...h, host: h.host.trim(), web_url: h.web_url?.trim() }))
+        .filter(h => h.host),
+    });
     message.success('设置已保存');
   };
 
@@ -61,6 +75,11 @@ const SettingsPage = () => {
 
   if (!localConfig) return null;
 
+  const gitHosts = localConfig.git_hosts || [];
+  const setGitHosts = (hosts: GitHostConfig[]) => setLocalConfig({ ...localConfig, git_hosts: hosts });
+  const updateGitHost = (index: number, patch: Partial<GitHostConfig>) =>
+    setGitHosts(gitHosts.map((h, i) => (i === index ? { ...h, ...patch } : h)));
+
   return (
     <div style={{ padding: 24 }} className="animate-fadeIn">
       <div style={{ display: 'flex', justifyContent: 'space-between', alignItems: 'center', marginBottom: 16 }}>
@@ -174,6 +193,97 @@ const SettingsPage = () => {
           </Space>
         </Card>
 
+        <Card title="Git" style={{ marginBottom: 12 }}>
+          <Text type="secondary" style={{ fontSize: 12 }}>Git 可执行文件（留空则使用系统 PATH 中的 git）</Text>
+          <Input
+            value={localConfig.git_path}
+            onChange={e => setLocalConfig({ ...localConfig, git_path: e.target.value })}
+            placeholder="/usr/local/bin/git"
+            style={{ fontFamily: 'monospace' }}
+          />
+          <div style={{ display: 'flex', gap: 16, marginTop: 12 }}>
+            <div style={{ flex: 1 }}>
+              <Text type="secondary" style={{ fontSize: 12 }}>代码片段预算（写入提示词的 diff 上限）</Text>
+              <InputNumber
+                value={localConfig.diff_excerpts?.token_budget ?? 1500}
+                onChange={v => setLocalConfig({
+                  ...localConfig,
+                  diff_excerpts: { ...localConfig.diff_excerpts, token_budget: v ?? 1500 },
+                })}
+                min={0} max={20000} step={500} addonAfter="tokens" style={{ width: '100%' }}
+              />
+            </div>
+            <div style={{ flex: 1 }}>
+              <Text type="secondary" style={{ fontSize: 12 }}>单个片段最多行数</Text>
+              <InputNumber
+                value={localConfig.diff_excerpts?.max_hunk_lines ?? 40}
+                onChange={v => setLocalConfig({
+                  ...localConfig,
+                  diff_excerpts: { ...localConfig.diff_excerpts, max_hunk_lines: v || 40 },
+                })}
+                min={5} max={200} style={{ width: '100%' }}
+              />
+            </div>
+          </div>
+          <div style={{ display: 'flex', gap: 16, marginTop: 12, alignItems: 'flex-end' }}>
+            <div style={{ flex: 1 }}>
+              <Text type="secondary" style={{ fontSize: 12 }}>批量改动判定：文件数</Text>
+              <InputNumber
+                value={localConfig.outliers?.max_files ?? 300}
+                onChange={v => setLocalConfig({
+                  ...localConfig,
+                  outliers: { ...localConfig.outliers, max_files: v || 300 },
+                })}
+                min={10} max={100000} style={{ width: '100%' }}
+              />
+            </div>
+            <div style={{ flex: 1 }}>
+              <Text type="secondary" style={{ fontSize: 12 }}>批量改动判定：改动行数</Text>
+              <InputNumber
+                value={localConfig.outliers?.max_churn ?? 10000}
+                onChange={v => setLocalConfig({
+                  ...localConfig,
+                  outliers: { ...localConfig.outliers, max_churn: v || 10000 },
+                })}
+                min={100} max={10000000} step={1000} addonAfter="行" style={{ width: '100%' }}
+              />
+            </div>
+            <div style={{ flex: 1 }}>
+              <Space>
+                <Switch
+                  checked={localConfig.outliers?.collapse_in_stats ?? true}
+                  onChange={v => setLocalConfig({
+                    ...localConfig,
+                    outliers: { ...localConfig.outliers, collapse_in_stats: v },
+                  })}
+                />
+                <Text style={{ fontSize: 12 }}>文件统计中折叠批量改动</Text>
+              </Space>
+            </div>
+          </div>
+        </Card>
+
+        <Card title="自建代码托管平台" style={{ marginBottom: 12 }}>
+          <Text type="secondary" style={{ fontSize: 12, display: 'block', marginBottom: 8 }}>
+            用于生成提交、文件和标签链接；网页地址留空则使用 https://主机名，SSH 主机名与网页域名不同时需填写
+          </Text>
+          {gitHosts.map((h, index) => (
+            <Space.Compact key={index} style={{ width: '100%', marginBottom: 8 }}>
+              <Input value={h.host} placeholder="git.example.com" style={{ fontFamily: 'monospace' }}
+                onChange={e => updateGitHost(index, { host: e.target.value })} />
+              <Select value={h.kind} options={HOST_KIND_OPTIONS} style={{ width: 180 }}
+                onChange={v => updateGitHost(index, { kind: v })} />
+              <Input value={h.web_url} placeholder="https://code.example.com" style={{ fontFamily: 'monospace' }}
+                onChange={e => updateGitHost(index, { web_url: e.target.value })} />
+              <Button icon={<DeleteOutlined />} onClick={() => setGitHosts(gitHosts.filter((_, i) => i !== index))} />
+            </Space.Compact>
+          ))}
+          <Button type="dashed" icon={<PlusOutlined />} block
+            onClick={() => setGitHosts([...gitHosts, { host: '', kind: 'gitlab', web_url: '' }])}>
+            添加平台
+          </Button>
+        </Card>
+
         <Card title="本地存储路径" style={{ marginBottom: 12 }}>
           <Descriptions column={1} size="small">
             <Descriptions.Item label="配置文件">
@@ -32,6 +32,15 @@ export interface ProjectConfig {
   repo_path: string;
   authors: string[];
   submodules: SubmoduleConfig[];
+  components?: ComponentMapping[];
+  use_codeowners?: boolean;
+  count_co_authored?: boolean;
+  include_automated?: boolean;
+}
+
+export interface ComponentMapping {
+  pattern: string;
+  component: string;
 }
 
 export interface GlobalConfig {
@@ -43,6 +52,128 @@ export interface GlobalConfig {
   save_reports: boolean;
   projects: ProjectConfig[];
   first_launch: boolean;
+  work_sessions: WorkSessionConfig;
+  timesheet: TimesheetConfig;
+  diff_excerpts: DiffExcerptConfig;
+  git_hosts: GitHostConfig[];
+  outliers: OutlierConfig;
+  git_path: string;
+}
+
+export type TimesheetField = 'date' | 'project' | 'hours' | 'summary' | 'commit_count' | 'constant';
+
+export interface TimesheetColumn {
+  field: TimesheetField;
+  header: string;
+  value?: string;
+}
+
+export interface TimesheetConfig {
+  columns: TimesheetColumn[];
+  date_format: string;
+  summary_max_chars: number;
+}
+
+export interface OutlierConfig {
+  max_files: number;
+  max_churn: number;
+  collapse_in_stats: boolean;
+}
+
+export type OutlierKind = 'whitespace_only' | 'many_files' | 'large_churn';
+
+export interface DiffExcerptConfig {
+  token_budget: number;
+  max_hunk_lines: number;
+}
+
+export type ExcerptKind = 'signature' | 'new_function' | 'test' | 'change';
+
+export interface DiffExcerpt {
+  hash: string;
+  message: string;
+  path: string;
+  kind: ExcerptKind;
+  content: string;
+  tokens: number;
+}
+
+export interface MemberStats {
+  name: string;
+  email: string;
+  commit_count: number;
+  lines_added: number;
+  lines_deleted: number;
+  lines_churned: number;
+  component_summary: ComponentSummary[];
+  tickets: string[];
+  sample_commits: GitCommit[];
+}
+
+export interface TeamBreakdown {
+  members: MemberStats[];
+}
+
+export interface PeriodAggregate {
+  since: string;
+  until: string;
+  commit_count: number;
+  lines_added: number;
+  lines_deleted: number;
+  lines_churned: number;
+  files_changed: number;
+  components: string[];
+  tickets: string[];
+}
+
+export interface PeriodDelta {
+  commit_count: number;
+  lines_added: number;
+  lines_deleted: number;
+  lines_churned: number;
+  files_changed: number;
+  component_count: number;
+  ticket_count: number;
+  commit_change_rate?: number | null;
+  churn_change_rate?: number | null;
+  new_components: string[];
+  dropped_components: string[];
+}
+
+export interface PeriodComparison {
+  current: PeriodAggregate;
+  previous: PeriodAggregate;
+  delta: PeriodDelta;
+}
+
+export interface SurvivalEntry {
+  name: string;
+  lines_added: number;
+  lines_surviving: number;
+  survival_rate: number;
+}
+
+export interface SurvivalReport {
+  lines_added: number;
+  lines_surviving: number;
+  survival_rate: number;
+  files_blamed: number;
+  files_skipped: number;
+  repos: SurvivalEntry[];
+  components: SurvivalEntry[];
+}
+
+export interface DiffExcerptReport {
+  excerpts: DiffExcerpt[];
+  token_budget: number;
+  used_tokens: number;
+  candidate_count: number;
+  text: string;
+}
+
+export interface WorkSessionConfig {
+  gap_minutes: number;
+  lead_in_minutes: number;
 }
 
 export interface GitCommit {
@@ -52,11 +183,109 @@ export interface GitCommit {
   date: string;
   message: string;
   files: GitFile[];
+  components: string[];
+  signature?: CommitSignature | null;
+  note?: string | null;
+  web_url?: string | null;
+  co_authors: string[];
+  via_co_author: boolean;
+  patch_id?: string | null;
+  change_id?: string | null;
+  landed: CommitLocation[];
+  reverts?: string | null;
+  reverted_by?: string | null;
+  automation?: AutomationKind | null;
+  outlier?: OutlierKind | null;
+  branch?: string | null;
+  repo?: string;
+}
+
+export type AutomationKind = 'bot' | 'dependency' | 'release' | 'formatting' | 'mass_rename';
+
+export interface CommitLocation {
+  hash: string;
+  repo: string;
+  date: string;
+}
+
+export type SignatureStatus =
+  | 'good'
+  | 'bad'
+  | 'untrusted'
+  | 'expired_signature'
+  | 'expired_key'
+  | 'revoked_key'
+  | 'unknown_key';
+
+export interface CommitSignature {
+  status: SignatureStatus;
+  signer: string;
+  key_id: string;
 }
 
 export interface GitFile {
   status: string;
   path: string;
+  components: string[];
+  web_url?: string | null;
+  asset?: AssetKind | null;
+  size?: number | null;
+}
+
+export type AssetKind = 'lfs' | 'binary';
+
+export interface AssetUpdate {
+  path: string;
+  kind: AssetKind;
+  size?: number | null;
+  change_count: number;
+}
+
+export interface AssetSummary {
+  file_count: number;
+  total_size: number;
+  updates: AssetUpdate[];
+}
+
+export type HostKind = 'github' | 'gitlab' | 'gitea' | 'gitee' | 'bitbucket';
+
+export interface GitHostConfig {
+  host: string;
+  kind: HostKind;
+  web_url?: string;
+}
+
+export interface GitRemote {
+  name: string;
+  url: string;
+  kind: HostKind | null;
+  web_url: string | null;
+}
+
+export interface GitTag {
+  name: string;
+  hash: string;
+  date: string;
+  web_url: string | null;
+}
+
+export interface GitFeatures {
+  mailmap: boolean;
+  name_status_z: boolean;
+  end_of_options: boolean;
+  worktree_porcelain: boolean;
+  annotate_stdin: boolean;
+}
+
+export interface GitCapabilities {
+  executable: string;
+  version: string;
+  major: number;
+  minor: number;
+  patch: number;
+  min_version: string;
+  supported: boolean;
+  features: GitFeatures;
 }
 
 export interface GitSubmodule {
@@ -64,6 +293,14 @@ export interface GitSubmodule {
   path: string;
 }
 
+export type RepoKind = 'work_tree' | 'bare' | 'bundle';
+
+export interface RepoIdentity {
+  kind: RepoKind;
+  common_dir: string;
+  worktrees: string[];
+}
+
 export interface GitStats {
   total_commits: number;
   total_files_changed: number;
@@ -71,6 +308,44 @@ export interface GitStats {
   date_range: [string, string];
   sample_commits: GitCommit[];
   file_changes_summary: FileChangeSummary[];
+  component_summary: ComponentSummary[];
+  signature_summary: SignatureSummary;
+  revert_summary: RevertSummary;
+  automated_count: number;
+  outlier_count: number;
+  pull_requests: PullRequest[];
+  pull_requests_truncated: boolean;
+  asset_summary: AssetSummary;
+}
+
+export interface PullRequest {
+  number: number;
+  title: string;
+  source_branch?: string | null;
+  target_branch?: string | null;
+  platform: HostKind;
+  merge_hash: string;
+  merged_at: string;
+  merged_by: string;
+  commits: string[];
+}
+
+export interface RevertPair {
+  revert_hash: string;
+  revert_message: string;
+  reverted_hash: string;
+}
+
+export interface RevertSummary {
+  revert_count: number;
+  reverted_count: number;
+  pairs: RevertPair[];
+}
+
+export interface SignatureSummary {
+  signed: number;
+  verified: number;
+  unsigned: number;
 }
 
 export interface FileChangeSummary {
@@ -78,6 +353,104 @@ export interface FileChangeSummary {
   change_count: number;
 }
 
+export interface ComponentSummary {
+  name: string;
+  commit_count: number;
+  file_count: number;
+}
+
+export type ChurnBucket = 'day' | 'week' | 'month';
+
+export interface ChurnEntry {
+  path: string;
+  change_count: number;
+  lines_added: number;
+  lines_deleted: number;
+  lines_churned: number;
+  author_count: number;
+}
+
+export interface ChurnPoint {
+  period: string;
+  change_count: number;
+  lines_churned: number;
+}
+
+export interface HotspotSeries {
+  path: string;
+  points: ChurnPoint[];
+}
+
+export interface ChurnAnalysis {
+  files: ChurnEntry[];
+  directories: ChurnEntry[];
+  hotspots: HotspotSeries[];
+  bucket: ChurnBucket;
+}
+
+export interface DayActivity {
+  date: string;
+  commit_count: number;
+}
+
+export interface MonthActivity {
+  month: string;
+  commit_count: number;
+}
+
+export interface ProjectActivity {
+  name: string;
+  commit_count: number;
+}
+
+export interface ActivityHeatmap {
+  total_commits: number;
+  calendar: DayActivity[];
+  hour_weekday: number[][];
+  months: MonthActivity[];
+  projects: ProjectActivity[];
+  most_active_month: string | null;
+  after_hours_commits: number;
+  weekend_commits: number;
+}
+
+export interface ProjectHours {
+  name: string;
+  hours: number;
+}
+
+export interface WorkSession {
+  start: string;
+  end: string;
+  commit_count: number;
+  hours: number;
+  projects: ProjectHours[];
+  subjects: string[];
+}
+
+export interface DayHours {
+  date: string;
+  hours: number;
+  projects: ProjectHours[];
+}
+
+export interface WorkSessionReport {
+  total_hours: number;
+  sessions: WorkSession[];
+  days: DayHours[];
+  projects: ProjectHours[];
+}
+
+export interface TimesheetRow {
+  date: string;
+  project: string;
+  hours: number;
+  commit_count: number;
+  summary: string;
+}
+
+export type TimesheetFormat = 'csv' | 'xlsx';
+
 export interface Report {
   id: string;
   project_name: string;
@@ -32,17 +32,28 @@ ${commitSummary}
 - 如有新功能，描述功能的价值和作用`;
 };
 
+export const formatSize = (bytes: number): string => {
+  if (bytes >= 1024 ** 3) return `${(bytes / 1024 ** 3).toFixed(1)} GB`;
+  if (bytes >= 1024 ** 2) return `${(bytes / 1024 ** 2).toFixed(1)} MB`;
+  if (bytes >= 1024) return `${(bytes / 1024).toFixed(1)} KB`;
+  return `${bytes} B`;
+};
+
 export const buildPromptFromStats = (
   stats: GitStats,
   reportType: ReportType,
   wordCount: number,
   projectName: string,
-  timeRangeText: string
+  timeRangeText: string,
+  diffExcerpts = '',
+  codeSurvival = '',
+  periodTrend = '',
+  teamBreakdown = ''
 ): string => {
   // 提取主要修改的模块（从文件路径推断）
   const moduleGroups = new Map<string, string[]>();
   stats.sample_commits.forEach(c => {
-    c.files.forEach(f => {
+    c.files.filter(f => !f.asset).forEach(f => {
       const parts = f.path.split('/');
       const module = parts.length > 1 ? parts[0] : '其他';
       if (!moduleGroups.has(module)) {
@@ -52,16 +63,96 @@ export const buildPromptFromStats = (
     });
   });
 
-  const moduleInfo = Array.from(moduleGroups.entries())
-    .map(([module, messages]) => `${module} (${messages.length}次提交)`)
-    .slice(0, 5)
+  // 配置了模块映射或 CODEOWNERS 时优先使用业务模块名
+  const moduleInfo = stats.component_summary.length > 0
+    ? stats.component_summary
+      .slice(0, 5)
+      .map(c => `${c.name} (${c.commit_count}次提交)`)
+      .join(', ')
+    : Array.from(moduleGroups.entries())
+      .map(([module, messages]) => `${module} (${messages.length}次提交)`)
+      .slice(0, 5)
+      .join(', ');
+
+  // 团队按功能分支安排任务，有分支信息时一并提供
+  const branchCounts = new Map<string, number>();
+  stats.sample_commits.forEach(c => {
+    if (c.branch) branchCounts.set(c.branch, (branchCounts.get(c.branch) ?? 0) + 1);
+  });
+  const branchInfo = Array.from(branchCounts.entries())
+    .sort((a, b) => b[1] - a[1])
+    .slice(0, 8)
+    .map(([branch, count]) => `${branch} (${count}次提交)`)
     .join(', ');
 
   const commitList = stats.sample_commits
     .slice(0, 20)
-    .map(c => `- ${c.message}`)
+    .map(c => {
+      const tags = [
+        c.branch ? `分支：${c.branch}` : '',
+        c.via_co_author ? '结对协作' : '',
+        c.reverts ? '回滚提交' : '',
+        c.reverted_by ? '已被回滚' : '',
+        c.automation ? '自动化提交' : '',
+        c.files.length > 0 && c.files.every(f => f.asset) ? '资源更新' : '',
+        c.outlier === 'whitespace_only' ? '仅格式调整' : c.outlier ? `批量改动（${c.files.length}个文件）` : '',
+        c.note ? `备注：${c.note.replace(/\s+/g, ' ')}` : '',
+      ].filter(Boolean);
+      return tags.length > 0 ? `- ${c.message}（${tags.join('；')}）` : `- ${c.message}`;
+    })
     .join('\n');
 
+  // PR 标题通常比单条提交说明更接近任务描述
+  const prList = stats.pull_requests
+    .slice(0, 15)
+    .map(pr => `- #${pr.number} ${pr.title || pr.source_branch || ''}（${pr.commits.length}个提交）`)
+    .join('\n');
+  const prTruncated = stats.pull_requests_truncated ? '（合并提交较多，仅统计了最近的部分）' : '';
+  const prSection = prList ? `\n已合并的 PR / MR${prTruncated}：\n${prList}\n` : '';
+
+  // 贴图、模型等资源文件只按目录概括，避免报告中罗列大量文件路径
+  const assets = stats.asset_summary;
+  const assetDirs = new Map<string, number>();
+  assets.updates.forEach(a => {
+    const dir = a.path.includes('/') ? a.path.slice(0, a.path.lastIndexOf('/')) : '根目录';
+    assetDirs.set(dir, (assetDirs.get(dir) ?? 0) + 1);
+  });
+  const assetDirInfo = Array.from(assetDirs.entries())
+    .sort((a, b) => b[1] - a[1])
+    .slice(0, 5)
+    .map(([dir, count]) => `${dir} (${count}个文件)`)
+    .join(', ');
+  const assetSection = assets.file_count > 0
+    ? `\n资源更新（LFS / 二进制文件，不计入代码改动）：${assets.file_count}个文件，共${formatSize(assets.total_size)}${assetDirInfo ? `\n主要目录：${assetDirInfo}` : ''}\n`
+    : '';
+  const assetNote = assetSection ? '\n- 资源文件只概括为资源更新，不要逐个列出文件路径' : '';
+
+  // 提交标题信息量不足时，关键代码片段帮助理解实际改动
+  const excerptSection = diffExcerpts.trim()
+    ? `\n关键代码改动（节选）：\n${diffExcerpts.trim()}\n`
+    : '';
+
+  // 留存行数比改动行数更能体现实际产出，反复返工的代码留存率低
+  const survivalSection = codeSurvival.trim()
+    ? `\n代码留存（本期新增代码中至今仍保留在 HEAD 的行数）：\n${codeSurvival.trim()}\n`
+    : '';
+
+  const trendSection = periodTrend.trim()
+    ? `\n与上一周期对比：\n${periodTrend.trim()}\n`
+    : '';
+  const trendNote = trendSection ? '\n- 结合与上一周期的对比描述变化趋势，不要只罗列数字' : '';
+
+  // 团队报告为每位成员单独成节
+  const teamSection = teamBreakdown.trim()
+    ? `\n成员分工：\n${teamBreakdown.trim()}\n`
+    : '';
+  const teamNote = teamSection ? '\n- 这是团队报告，按成员分节总结每个人的工作，最后概括团队整体进展' : '';
+
+  // 同一时间段内做了又撤回的改动不应算作成果
+  const revertNote = stats.revert_summary.reverted_count > 0
+    ? `\n- 有${stats.revert_summary.reverted_count}条提交在本期内已被回滚，不要作为成果列出`
+    : '';
+
   // 根据报告类型调整格式
   let formatExample = '';
   let requirements = '';
@@ -112,11 +203,11 @@ export const buildPromptFromStats = (
 项目：${projectName}
 时间：${timeRangeText}
 提交数：${stats.total_commits}条
-主要模块：${moduleInfo}
+主要模块：${moduleInfo}${branchInfo ? `\n功能分支：${branchInfo}` : ''}
 
 提交记录（抽样）：
 ${commitList}
-
+${prSection}${assetSection}${excerptSection}${survivalSection}${trendSection}${teamSection}
 参考格式：
 ${formatExample}
 
@@ -126,7 +217,7 @@ ${requirements}
 - 合并相似提交，提炼关键信息
 - 不要逐条翻译commit，要归纳总结
 - 如果一个功能有多次提交，只写一条
-- 强调结果、影响、价值，而非过程
+- 强调结果、影响、价值，而非过程${revertNote}${assetNote}${trendNote}${teamNote}
 
 注意：实际有${stats.total_commits}条提交，以上仅为抽样。`;
 };
@@ -42,6 +42,49 @@ export const getTimeRangeByType = (
   };
 };
 
+/**
+ * 上一周期的同期，用于环比：上周、上月、上季度、去年；日报对比前一天。
+ * 本期从周期起点到现在，上期同样从上一周期起点开始、取相同的时长（不超过上一周期末），
+ * 避免月初把几天的数据与上月整月相比
+ */
+export const getPreviousTimeRangeByType = (
+  reportType: ReportType,
+  since: string,
+  until: string
+): { since: string; until: string } => {
+  const unit = reportType === 'weekly' ? 'week'
+    : reportType === 'monthly' ? 'month'
+      : reportType === 'quarterly' ? 'quarter'
+        : reportType === 'yearly' ? 'year'
+          : 'day';
+  const start = dayjs(since);
+  const elapsed = dayjs(until).diff(start, 'second');
+  const previousStart = start.subtract(1, unit).startOf(unit);
+  const previousEnd = previousStart.add(elapsed, 'second');
+  const periodEnd = previousStart.endOf(unit);
+
+  return {
+    since: previousStart.format('YYYY-MM-DD HH:mm:ss'),
+    until: (previousEnd.isAfter(periodEnd) ? periodEnd : previousEnd).format('YYYY-MM-DD HH:mm:ss'),
+  };
+};
+
+/** 自定义范围的上期为紧邻其前、长度相同的时间段 */
+export const getPreviousCustomRange = (
+  since: string,
+  until: string
+): { since: string; until: string } => {
+  const start = dayjs(since);
+  const end = dayjs(until);
+  const length = end.diff(start, 'second');
+  const previousUntil = start.subtract(1, 'second');
+
+  return {
+    since: previousUntil.subtract(length, 'second').format('YYYY-MM-DD HH:mm:ss'),
+    until: previousUntil.format('YYYY-MM-DD HH:mm:ss'),
+  };
+};
+
 export const getTimeRange = (
   preset: TimeRangePreset,
   crossDay: boolean,